@@ -0,0 +1,348 @@
+use num::Float;
+
+use std::fmt;
+
+use crate::{FloatMatrix, Vector};
+
+/// A square tridiagonal matrix, storing only its sub-diagonal, diagonal and super-diagonal.
+pub struct TridiagonalMatrix<T: Float> {
+  sub: Vec<T>,
+  diag: Vec<T>,
+  sup: Vec<T>
+}
+
+/// A square banded matrix with `kl` sub-diagonals and `ku` super-diagonals.
+///
+/// Row `i` stores the values of columns `i - kl` through `i + ku`, so the storage is
+/// `n * (kl + ku + 1)` values instead of `n * n`.
+pub struct BandedMatrix<T: Float> {
+  n: usize,
+  kl: usize,
+  ku: usize,
+  band: Vec<T>
+}
+
+/// The LU factorization with partial pivoting of a [BandedMatrix], which can be reused to solve
+/// several systems with the same matrix.
+pub struct BandedLu<T: Float> {
+  n: usize,
+  kl: usize,
+  width: usize,
+  factors: Vec<T>,
+  pivots: Vec<usize>
+}
+
+impl<T: Float> TridiagonalMatrix<T> {
+  /// Creates an `n x n` tridiagonal matrix from its sub-diagonal (length `n - 1`), diagonal
+  /// (length `n`) and super-diagonal (length `n - 1`).
+  pub fn new(sub: Vec<T>, diag: Vec<T>, sup: Vec<T>) -> Result<Self, String> {
+    if diag.is_empty() || (sub.len() + 1 != diag.len()) || (sup.len() + 1 != diag.len()) {
+      let s = format!("Cannot build a tridiagonal matrix from diagonals of length {}, {} and {}",
+                              sub.len(), diag.len(), sup.len());
+      return Err(s);
+    }
+
+    Ok(TridiagonalMatrix { sub, diag, sup })
+  }
+
+  pub fn size(&self) -> (usize, usize) {
+
+    (self.diag.len(), self.diag.len())
+  }
+
+  /// Returns the value at `(i, j)`, which is zero outside of the three diagonals.
+  pub fn get(&self, i: usize, j: usize) -> T {
+    let n = self.diag.len();
+    if (i >= n) || (j >= n) {
+      panic!("Index ({}, {}) is out of bounds for a matrix of size ({}, {})", i, j, n, n);
+    }
+
+    if i == j {
+      self.diag[i]
+    }
+    else if i == j + 1 {
+      self.sub[j]
+    }
+    else if j == i + 1 {
+      self.sup[i]
+    }
+    else {
+      T::zero()
+    }
+  }
+
+  /// Multiplies the matrix by the vector `x` in `O(n)`.
+  pub fn mul_vec(&self, x: &Vector<T>) -> Result<Vector<T>, String> {
+    let n = self.diag.len();
+    if x.len() != n {
+      let s = format!("Cannot multiply a matrix of size ({}, {}) and a vector of length {} together", n, n, x.len());
+      return Err(s);
+    }
+
+    let mut y: Vector<T> = Vector::new(n);
+    for i in 0..n {
+      let mut sum = self.diag[i] * x[i];
+      if i > 0 {
+        sum = sum + (self.sub[i - 1] * x[i - 1]);
+      }
+      if i + 1 < n {
+        sum = sum + (self.sup[i] * x[i + 1]);
+      }
+      y[i] = sum;
+    }
+
+    Ok(y)
+  }
+
+  /// Solves `Ax = b` in `O(n)` with the Thomas algorithm.
+  ///
+  /// The algorithm does not pivot, so it is only guaranteed to be stable for diagonally dominant
+  /// or symmetric positive-definite matrices. A zero pivot is reported as an error; use
+  /// [BandedMatrix::solve] for those systems.
+  pub fn solve(&self, b: &Vector<T>) -> Result<Vector<T>, String> {
+    let n = self.diag.len();
+    if b.len() != n {
+      let s = format!("Cannot solve a system of size ({}, {}) with a vector of length {}", n, n, b.len());
+      return Err(s);
+    }
+
+    let mut c: Vec<T> = vec![T::zero(); n];
+    let mut d: Vec<T> = vec![T::zero(); n];
+    for i in 0..n {
+      let (a, upper) = if i > 0 { (self.sub[i - 1], c[i - 1]) } else { (T::zero(), T::zero()) };
+      let pivot = self.diag[i] - (a * upper);
+      if pivot == T::zero() {
+        return Err(format!("Zero pivot in row {} of the Thomas algorithm", i));
+      }
+
+      if i + 1 < n {
+        c[i] = self.sup[i] / pivot;
+      }
+      let prev = if i > 0 { d[i - 1] } else { T::zero() };
+      d[i] = (b[i] - (a * prev)) / pivot;
+    }
+
+    let mut x: Vector<T> = Vector::new(n);
+    x[n - 1] = d[n - 1];
+    for i in (0..(n - 1)).rev() {
+      x[i] = d[i] - (c[i] * x[i + 1]);
+    }
+
+    Ok(x)
+  }
+
+  /// Converts the matrix to a [BandedMatrix] with one sub-diagonal and one super-diagonal.
+  pub fn to_banded(&self) -> BandedMatrix<T> {
+    let n = self.diag.len();
+    let mut banded = BandedMatrix::new(n, 1, 1);
+    for i in 0..n {
+      banded.set(i, i, self.diag[i]);
+      if i + 1 < n {
+        banded.set(i + 1, i, self.sub[i]);
+        banded.set(i, i + 1, self.sup[i]);
+      }
+    }
+
+    banded
+  }
+}
+
+impl<T: Float + fmt::Debug> TridiagonalMatrix<T> {
+  pub fn to_dense(&self) -> FloatMatrix<T> {
+
+    self.to_banded().to_dense()
+  }
+}
+
+impl<T: Float> BandedMatrix<T> {
+  /// Creates an `n x n` banded matrix with `kl` sub-diagonals and `ku` super-diagonals, all zero.
+  pub fn new(n: usize, kl: usize, ku: usize) -> Self {
+    if n < 1 {
+      panic!("Cannot initialize a matrix with rows or columns less than 1");
+    }
+
+    BandedMatrix { n, kl, ku, band: vec![T::zero(); n * (kl + ku + 1)] }
+  }
+
+  pub fn size(&self) -> (usize, usize) {
+
+    (self.n, self.n)
+  }
+
+  /// Returns the number of `(sub, super)` diagonals.
+  pub fn bandwidth(&self) -> (usize, usize) {
+
+    (self.kl, self.ku)
+  }
+
+  fn in_band(&self, i: usize, j: usize) -> bool {
+
+    (j + self.kl >= i) && (j <= i + self.ku)
+  }
+
+  fn offset(&self, i: usize, j: usize) -> usize {
+
+    (i * (self.kl + self.ku + 1)) + (j + self.kl - i)
+  }
+
+  /// Returns the value at `(i, j)`, which is zero outside of the band.
+  pub fn get(&self, i: usize, j: usize) -> T {
+    if (i >= self.n) || (j >= self.n) {
+      panic!("Index ({}, {}) is out of bounds for a matrix of size ({}, {})", i, j, self.n, self.n);
+    }
+
+    if self.in_band(i, j) { self.band[self.offset(i, j)] } else { T::zero() }
+  }
+
+  /// Sets the value at `(i, j)`. Panics if `(i, j)` is outside of the band.
+  pub fn set(&mut self, i: usize, j: usize, val: T) {
+    if (i >= self.n) || (j >= self.n) || !self.in_band(i, j) {
+      panic!("Index ({}, {}) is outside the band of a ({}, {}) matrix with bandwidth ({}, {})",
+             i, j, self.n, self.n, self.kl, self.ku);
+    }
+
+    let offset = self.offset(i, j);
+    self.band[offset] = val;
+  }
+
+  /// Multiplies the matrix by the vector `x` in `O(n * (kl + ku))`.
+  pub fn mul_vec(&self, x: &Vector<T>) -> Result<Vector<T>, String> {
+    if x.len() != self.n {
+      let s = format!("Cannot multiply a matrix of size ({}, {}) and a vector of length {} together",
+                              self.n, self.n, x.len());
+      return Err(s);
+    }
+
+    let mut y: Vector<T> = Vector::new(self.n);
+    for i in 0..self.n {
+      let mut sum = T::zero();
+      for j in i.saturating_sub(self.kl)..(i + self.ku + 1).min(self.n) {
+        sum = sum + (self.band[self.offset(i, j)] * x[j]);
+      }
+      y[i] = sum;
+    }
+
+    Ok(y)
+  }
+
+  /// Computes the LU factorization with partial pivoting in `O(n * kl * (kl + ku))`.
+  pub fn lu(&self) -> Result<BandedLu<T>, String> {
+    let (n, kl, ku) = (self.n, self.kl, self.ku);
+    // Pivoting can fill in up to kl extra super-diagonals of U.
+    let width = (2 * kl) + ku + 1;
+    let at = |i: usize, j: usize| (i * width) + (j + kl - i);
+
+    let mut factors: Vec<T> = vec![T::zero(); n * width];
+    for i in 0..n {
+      for j in i.saturating_sub(kl)..(i + ku + 1).min(n) {
+        factors[at(i, j)] = self.band[self.offset(i, j)];
+      }
+    }
+
+    let mut pivots: Vec<usize> = Vec::with_capacity(n);
+    for k in 0..n {
+      let last_row = (k + kl).min(n - 1);
+      let last_col = (k + kl + ku).min(n - 1);
+
+      let mut p = k;
+      for i in (k + 1)..=last_row {
+        if factors[at(i, k)].abs() > factors[at(p, k)].abs() {
+          p = i;
+        }
+      }
+      if factors[at(p, k)] == T::zero() {
+        return Err(format!("Matrix is singular, no pivot found in column {}", k));
+      }
+      pivots.push(p);
+
+      if p != k {
+        for j in k..=last_col {
+          factors.swap(at(k, j), at(p, j));
+        }
+      }
+
+      let pivot = factors[at(k, k)];
+      for i in (k + 1)..=last_row {
+        let l = factors[at(i, k)] / pivot;
+        factors[at(i, k)] = l;
+        for j in (k + 1)..=last_col {
+          factors[at(i, j)] = factors[at(i, j)] - (l * factors[at(k, j)]);
+        }
+      }
+    }
+
+    Ok(BandedLu { n, kl, width, factors, pivots })
+  }
+
+  /// Solves `Ax = b` with banded LU factorization.
+  pub fn solve(&self, b: &Vector<T>) -> Result<Vector<T>, String> {
+
+    self.lu()?.solve(b)
+  }
+}
+
+impl<T: Float + fmt::Debug> BandedMatrix<T> {
+  /// Creates a banded matrix from the band of a square `FloatMatrix`, ignoring values outside of it.
+  pub fn from_dense(m: &FloatMatrix<T>, kl: usize, ku: usize) -> Result<Self, String> {
+    let (rows, cols) = m.size();
+    if rows != cols {
+      let s = format!("Matrix is not square! Size: ({}, {})", rows, cols);
+      return Err(s);
+    }
+
+    let mut banded = BandedMatrix::new(rows, kl, ku);
+    for i in 0..rows {
+      for j in i.saturating_sub(kl)..(i + ku + 1).min(rows) {
+        banded.set(i, j, m[(i, j)]);
+      }
+    }
+
+    Ok(banded)
+  }
+
+  pub fn to_dense(&self) -> FloatMatrix<T> {
+    let mut dense: FloatMatrix<T> = FloatMatrix::new(self.n, self.n);
+    for i in 0..self.n {
+      for j in i.saturating_sub(self.kl)..(i + self.ku + 1).min(self.n) {
+        dense[(i, j)] = self.band[self.offset(i, j)];
+      }
+    }
+
+    dense
+  }
+}
+
+impl<T: Float> BandedLu<T> {
+  /// Solves `Ax = b` using the factorization of `A`.
+  pub fn solve(&self, b: &Vector<T>) -> Result<Vector<T>, String> {
+    let (n, kl, width) = (self.n, self.kl, self.width);
+    if b.len() != n {
+      let s = format!("Cannot solve a system of size ({}, {}) with a vector of length {}", n, n, b.len());
+      return Err(s);
+    }
+    let at = |i: usize, j: usize| (i * width) + (j + kl - i);
+
+    let mut x = b.clone();
+    for k in 0..n {
+      let p = self.pivots[k];
+      if p != k {
+        let temp = x[k];
+        x[k] = x[p];
+        x[p] = temp;
+      }
+      for i in (k + 1)..=(k + kl).min(n - 1) {
+        x[i] = x[i] - (self.factors[at(i, k)] * x[k]);
+      }
+    }
+
+    for i in (0..n).rev() {
+      let mut sum = x[i];
+      for j in (i + 1)..=(i + width - kl - 1).min(n - 1) {
+        sum = sum - (self.factors[at(i, j)] * x[j]);
+      }
+      x[i] = sum / self.factors[at(i, i)];
+    }
+
+    Ok(x)
+  }
+}
@@ -0,0 +1,63 @@
+use std::fmt;
+
+/// The error type for matrix operations that check shapes, indices or values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MatrixError {
+  /// No matrices were given to an operation that needs at least one.
+  Empty,
+  /// Matrices placed side by side have different numbers of rows.
+  RowMismatch { expected: usize, found: usize },
+  /// Matrices stacked on top of each other have different numbers of columns.
+  ColumnMismatch { expected: usize, found: usize },
+  /// A split index is zero, not increasing, or not less than the length being split.
+  InvalidSplit { index: usize, len: usize },
+  /// The number of values does not match the number the matrix needs.
+  SizeMismatch { expected: usize, found: usize },
+  /// A row or column index is past the end of the matrix.
+  IndexOutOfBounds { index: usize, len: usize },
+  /// The operation would leave the matrix with zero rows or columns.
+  InvalidDimensions { rows: usize, cols: usize },
+  /// The operation is only defined for square matrices.
+  NotSquare { rows: usize, cols: usize },
+  /// An integer operation overflowed while computing value `(row, col)`.
+  Overflow { row: usize, col: usize },
+  /// The matrix is singular, so it has no inverse.
+  Singular,
+  /// An iterative method did not converge within the given number of iterations.
+  NotConverged { iterations: usize },
+  /// The system of equations has no solution.
+  NoSolution,
+  /// A modular operation was given a modulus less than 1.
+  InvalidModulus
+}
+
+impl fmt::Display for MatrixError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      MatrixError::Empty => write!(f, "Cannot build a matrix from an empty list of blocks"),
+      MatrixError::RowMismatch { expected, found } =>
+        write!(f, "Expected a block with {} rows, found one with {} rows", expected, found),
+      MatrixError::ColumnMismatch { expected, found } =>
+        write!(f, "Expected a block with {} columns, found one with {} columns", expected, found),
+      MatrixError::InvalidSplit { index, len } =>
+        write!(f, "Cannot split a dimension of length {} at index {}", len, index),
+      MatrixError::SizeMismatch { expected, found } =>
+        write!(f, "Expected {} values, found {}", expected, found),
+      MatrixError::IndexOutOfBounds { index, len } =>
+        write!(f, "Index {} is out of bounds for a dimension of length {}", index, len),
+      MatrixError::InvalidDimensions { rows, cols } =>
+        write!(f, "Cannot create a matrix of size ({}, {})", rows, cols),
+      MatrixError::NotSquare { rows, cols } =>
+        write!(f, "Matrix is not square! Size: ({}, {})", rows, cols),
+      MatrixError::Overflow { row, col } =>
+        write!(f, "Arithmetic overflow while computing the value at ({}, {})", row, col),
+      MatrixError::Singular => write!(f, "Matrix is singular"),
+      MatrixError::NotConverged { iterations } =>
+        write!(f, "Failed to converge after {} iterations", iterations),
+      MatrixError::NoSolution => write!(f, "The system has no solution"),
+      MatrixError::InvalidModulus => write!(f, "Cannot reduce values modulo a number less than 1")
+    }
+  }
+}
+
+impl std::error::Error for MatrixError {}
@@ -3,6 +3,26 @@ use num::{
   NumCast
 };
 
+use crate::error::MatrixError;
+use crate::kernel::{Operand, gemm};
+use crate::layout::Layout;
+use crate::parallel::{MaybeSync, for_each_chunk};
+use crate::simd;
+use crate::strassen::strassen;
+
+use crate::view::{
+  IndexedIterator,
+  MatrixCols,
+  MatrixIterMut,
+  MatrixRows,
+  MatrixView,
+  MatrixViewMut,
+  check_block,
+  resolve_range,
+  split_ranges,
+  view_range
+};
+
 use std::{
   fmt, 
   ops::{
@@ -10,7 +30,8 @@ use std::{
     Sub,
     Mul,
     Index,
-    IndexMut
+    IndexMut,
+    RangeBounds
   }
 };
 
@@ -18,6 +39,7 @@ use std::{
 pub struct FloatMatrix<T: Float> {
   rows: usize,
   cols: usize,
+  layout: Layout,
   matrix: Vec<T>
 }
 
@@ -35,7 +57,7 @@ impl<T: Float + std::fmt::Debug> FloatMatrix<T> {
       mat.push(NumCast::from(0.0).unwrap());
     }
 
-    Some(FloatMatrix { rows: new_height, cols: new_width, matrix: mat })
+    Some(FloatMatrix { rows: new_height, cols: new_width, layout: Layout::RowMajor, matrix: mat })
   }
 
   /// Creates a new `row x column` float matrix with all values initalized to zero.
@@ -44,15 +66,10 @@ impl<T: Float + std::fmt::Debug> FloatMatrix<T> {
     Self::matrix(rows, columns).expect("Cannot initialize a matrix with rows or columns less than 1")
   }
 
-  pub fn from_1d_vec(matrix: Vec<T>, rows: usize, cols: usize) -> Self {
-
-    FloatMatrix {rows, cols, matrix}
-  }
-
   // Creates an iterator for the current matrix.
   pub fn iter(&self) -> FloatMatrixIterator<T> {
 
-    FloatMatrixIterator { inner: self, idx: 0 }
+    FloatMatrixIterator { inner: self, idx: 0, end: self.rows * self.cols }
   }
 
   pub fn size(&self) -> (usize, usize) {
@@ -60,15 +77,20 @@ impl<T: Float + std::fmt::Debug> FloatMatrix<T> {
     (self.rows, self.cols)
   }
 
+  /// Returns the transpose of the matrix by flipping its layout, so the values keep their order
+  /// in memory and transposing a row-major matrix gives a column-major one.
+  ///
+  /// [FloatMatrix::into_transpose] also avoids cloning the values, and [FloatMatrix::transpose_row_major]
+  /// reorders them instead.
   pub fn transpose(&self) -> FloatMatrix<T> {
-    let mut trans: Vec<T> = Vec::new();
-    for i in 0..self.cols {
-      for j in 0..self.rows {
-        trans.push(self.matrix[i + (j * self.cols)]);
-      }
-    }
 
-    FloatMatrix {rows: self.cols, cols: self.rows, matrix: trans}
+    FloatMatrix {rows: self.cols, cols: self.rows, layout: self.layout.transpose(), matrix: self.matrix.clone()}
+  }
+
+  /// Returns the transpose of the matrix as a new row-major matrix, reordering the values.
+  pub fn transpose_row_major(&self) -> FloatMatrix<T> {
+
+    FloatMatrix {rows: self.cols, cols: self.rows, layout: Layout::RowMajor, matrix: self.values(Layout::ColMajor)}
   }
 
   pub fn t(&self) -> FloatMatrix<T> {
@@ -77,7 +99,7 @@ impl<T: Float + std::fmt::Debug> FloatMatrix<T> {
   }
 
   pub fn rref(&self) -> FloatMatrix<T> {
-    let mut rref: Vec<T> = self.matrix.clone();
+    let mut rref: Vec<T> = self.values(Layout::RowMajor);
     
     for i in 0..self.rows {
       for j in i+1..self.rows {
@@ -100,41 +122,934 @@ impl<T: Float + std::fmt::Debug> FloatMatrix<T> {
 
     }
 
-    FloatMatrix {rows: self.rows, cols: self.cols, matrix: rref}
+    FloatMatrix {rows: self.rows, cols: self.cols, layout: Layout::RowMajor, matrix: rref}
   } 
+}
 
-  pub fn identity(size: usize) -> FloatMatrix<f32> {
-    let mut identity: FloatMatrix<f32> = FloatMatrix::new(size, size);
-  
+impl<T: Float> FloatMatrix<T> {
+  /// Returns the position of value `(i, j)` in the underlying storage.
+  fn offset(&self, i: usize, j: usize) -> usize {
+
+    self.layout.offset(i, j, self.rows, self.cols)
+  }
+
+  /// Borrows the matrix as an operand of the multiplication kernel.
+  fn operand(&self) -> Operand<'_, T> {
+
+    Operand::new(&self.matrix, self.rows, self.cols, self.layout)
+  }
+
+  /// Returns the storage position of the `idx`-th value in row-major order.
+  fn position(&self, idx: usize) -> usize {
+    match self.layout {
+      Layout::RowMajor => idx,
+      Layout::ColMajor => self.offset(idx / self.cols, idx % self.cols)
+    }
+  }
+
+  /// Returns the order in which the values of the matrix are stored.
+  pub fn layout(&self) -> Layout {
+
+    self.layout
+  }
+
+  /// Returns the values of the matrix in the order they are stored.
+  pub fn as_slice(&self) -> &[T] {
+
+    &self.matrix
+  }
+
+  /// Transposes the matrix without copying by flipping its layout.
+  pub fn into_transpose(self) -> FloatMatrix<T> {
+
+    FloatMatrix {rows: self.cols, cols: self.rows, layout: self.layout.transpose(), matrix: self.matrix}
+  }
+
+  /// Transposes the matrix in place, moving its values so that it keeps its layout.
+  ///
+  /// Square matrices swap values across the diagonal. Other shapes rotate each cycle of the
+  /// permutation from its smallest position, found by walking the cycle, so nothing is allocated.
+  pub fn transpose_in_place(&mut self) {
+    let (rows, cols) = match self.layout {
+      Layout::RowMajor => (self.rows, self.cols),
+      Layout::ColMajor => (self.cols, self.rows)
+    };
+    if rows == cols {
+      for i in 0..rows {
+        for j in (i + 1)..cols {
+          self.matrix.swap((i * cols) + j, (j * rows) + i);
+        }
+      }
+    }
+    else if self.matrix.len() > 2 {
+      // The value at `idx` moves to `idx * rows mod (len - 1)`, and the first and last stay put.
+      let last = self.matrix.len() - 1;
+      let next = |idx: usize| (idx * rows) % last;
+      for start in 1..last {
+        let mut idx = next(start);
+        while idx > start {
+          idx = next(idx);
+        }
+        if idx < start {
+          continue;
+        }
+        let mut idx = next(start);
+        while idx != start {
+          self.matrix.swap(start, idx);
+          idx = next(idx);
+        }
+      }
+    }
+
+    (self.rows, self.cols) = (self.cols, self.rows);
+  }
+
+  /// Checks that the matrix has size `(rows, cols)`.
+  fn expect_size(&self, rows: usize, cols: usize) -> Result<(), MatrixError> {
+    if self.rows != rows {
+      return Err(MatrixError::RowMismatch { expected: rows, found: self.rows });
+    }
+    if self.cols != cols {
+      return Err(MatrixError::ColumnMismatch { expected: cols, found: self.cols });
+    }
+
+    Ok(())
+  }
+
+  /// Returns the length of the rows or columns along which values are stored in `layout` order.
+  fn line_len(&self, layout: Layout) -> usize {
+    match layout {
+      Layout::RowMajor => self.cols,
+      Layout::ColMajor => self.rows
+    }
+  }
+
+  /// Copies row `k` of the matrix into `line` for [Layout::RowMajor], or column `k` for [Layout::ColMajor].
+  fn copy_line(&self, layout: Layout, k: usize, line: &mut [T]) {
+    for (l, value) in line.iter_mut().enumerate() {
+      let (i, j) = match layout {
+        Layout::RowMajor => (k, l),
+        Layout::ColMajor => (l, k)
+      };
+      *value = self.matrix[self.offset(i, j)];
+    }
+  }
+
+  /// Returns a copy of the values in `layout` order, made on the current thread.
+  fn values(&self, layout: Layout) -> Vec<T> {
+    let mut matrix: Vec<T> = vec![T::zero(); self.matrix.len()];
+    for (k, line) in matrix.chunks_mut(self.line_len(layout).max(1)).enumerate() {
+      self.copy_line(layout, k, line);
+    }
+
+    matrix
+  }
+
+  /// Returns the values in `layout` order, copying only if the layout changes.
+  fn into_values(self, layout: Layout) -> Vec<T> {
+    if self.layout == layout {
+      return self.matrix;
+    }
+
+    self.values(layout)
+  }
+
+  /// Returns a copy of the matrix with its values stored in `layout` order.
+  pub fn to_layout(&self, layout: Layout) -> FloatMatrix<T>
+    where T: MaybeSync {
+    let mut matrix: Vec<T> = vec![T::zero(); self.matrix.len()];
+    for_each_chunk(&mut matrix, self.line_len(layout), self.matrix.len(), |k, line| self.copy_line(layout, k, line));
+
+    FloatMatrix {rows: self.rows, cols: self.cols, layout, matrix}
+  }
+
+  /// Converts the matrix to store its values in `layout` order, copying only if the layout changes.
+  pub fn into_layout(self, layout: Layout) -> FloatMatrix<T>
+    where T: MaybeSync {
+    if self.layout == layout {
+      return self;
+    }
+
+    self.to_layout(layout)
+  }
+
+  /// Borrows the block of size `(rows, cols)` starting at `(row, col)` without copying.
+  pub fn view(&self, row: usize, col: usize, rows: usize, cols: usize) -> MatrixView<'_, T> {
+    check_block((self.rows, self.cols), row, col, rows, cols);
+    let (row_stride, col_stride) = self.layout.strides(self.rows, self.cols);
+    let range = view_range(self.offset(row, col), rows, cols, row_stride, col_stride);
+
+    MatrixView::new(&self.matrix[range], rows, cols, row_stride, col_stride)
+  }
+
+  /// Mutably borrows the block of size `(rows, cols)` starting at `(row, col)` without copying.
+  pub fn view_mut(&mut self, row: usize, col: usize, rows: usize, cols: usize) -> MatrixViewMut<'_, T> {
+    check_block((self.rows, self.cols), row, col, rows, cols);
+    let (row_stride, col_stride) = self.layout.strides(self.rows, self.cols);
+    let range = view_range(self.offset(row, col), rows, cols, row_stride, col_stride);
+
+    MatrixViewMut::new(&mut self.matrix[range], rows, cols, row_stride, col_stride)
+  }
+
+  /// Borrows row `i` as a `1 x columns` view.
+  pub fn row_view(&self, i: usize) -> MatrixView<'_, T> {
+
+    self.view(i, 0, 1, self.cols)
+  }
+
+  /// Mutably borrows row `i` as a `1 x columns` view. Works for either layout and panics if `i`
+  /// is out of bounds.
+  pub fn row_view_mut(&mut self, i: usize) -> MatrixViewMut<'_, T> {
+    let cols = self.cols;
+
+    self.view_mut(i, 0, 1, cols)
+  }
+
+  /// Borrows column `j` as a `rows x 1` view.
+  pub fn col_view(&self, j: usize) -> MatrixView<'_, T> {
+
+    self.view(0, j, self.rows, 1)
+  }
+
+  /// Mutably borrows column `j` as a `rows x 1` view. Works for either layout and panics if `j`
+  /// is out of bounds.
+  pub fn col_view_mut(&mut self, j: usize) -> MatrixViewMut<'_, T> {
+    let rows = self.rows;
+
+    self.view_mut(0, j, rows, 1)
+  }
+
+  /// Borrows the whole matrix as a view.
+  pub fn as_view(&self) -> MatrixView<'_, T> {
+
+    self.view(0, 0, self.rows, self.cols)
+  }
+
+  /// Borrows the block covered by a range of rows and a range of columns.
+  ///
+  /// `Index` can only return references into the matrix, so `m.slice(1..3, ..)` is the
+  /// equivalent of `m[(1..3, ..)]`.
+  pub fn slice<R: RangeBounds<usize>, C: RangeBounds<usize>>(&self, rows: R, cols: C) -> MatrixView<'_, T> {
+    let rows = resolve_range(rows, self.rows);
+    let cols = resolve_range(cols, self.cols);
+
+    self.view(rows.start, cols.start, rows.len(), cols.len())
+  }
+
+  /// Mutably borrows the block covered by a range of rows and a range of columns. Works for
+  /// either layout and panics if either range is out of bounds.
+  pub fn slice_mut<R: RangeBounds<usize>, C: RangeBounds<usize>>(&mut self, rows: R, cols: C) -> MatrixViewMut<'_, T> {
+    let rows = resolve_range(rows, self.rows);
+    let cols = resolve_range(cols, self.cols);
+
+    self.view_mut(rows.start, cols.start, rows.len(), cols.len())
+  }
+
+  /// Borrows row `i` as a `1 x columns` view, whatever the layout. Panics if `i` is out of bounds.
+  pub fn row(&self, i: usize) -> MatrixView<'_, T> {
+
+    self.row_view(i)
+  }
+
+  /// Mutably borrows row `i` as a `1 x columns` view, whatever the layout. Panics if `i` is out
+  /// of bounds.
+  pub fn row_mut(&mut self, i: usize) -> MatrixViewMut<'_, T> {
+
+    self.row_view_mut(i)
+  }
+
+  /// Returns row `i` as a slice, or `None` if `i` is out of bounds or the matrix is column-major,
+  /// where the values of a row are not contiguous.
+  pub fn row_slice(&self, i: usize) -> Option<&[T]> {
+    if (i >= self.rows) || (self.layout != Layout::RowMajor) {
+      return None;
+    }
+
+    Some(&self.matrix[(i * self.cols)..((i + 1) * self.cols)])
+  }
+
+  /// Returns row `i` as a mutable slice, or `None` if `i` is out of bounds or the matrix is
+  /// column-major.
+  pub fn row_slice_mut(&mut self, i: usize) -> Option<&mut [T]> {
+    if (i >= self.rows) || (self.layout != Layout::RowMajor) {
+      return None;
+    }
+
+    Some(&mut self.matrix[(i * self.cols)..((i + 1) * self.cols)])
+  }
+
+  /// Borrows column `j` as a `rows x 1` view.
+  pub fn col(&self, j: usize) -> MatrixView<'_, T> {
+
+    self.col_view(j)
+  }
+
+  /// Mutably borrows column `j` as a `rows x 1` view, whatever the layout. Panics if `j` is out
+  /// of bounds.
+  pub fn col_mut(&mut self, j: usize) -> MatrixViewMut<'_, T> {
+
+    self.col_view_mut(j)
+  }
+
+  /// Creates an iterator that yields mutable references to the values of the matrix in row-major
+  /// order, the same order as [iter](Self::iter), whatever the layout.
+  pub fn iter_mut(&mut self) -> MatrixIterMut<'_, T> {
+    let lines = match self.layout {
+      Layout::RowMajor => 1,
+      Layout::ColMajor => self.cols
+    };
+
+    MatrixIterMut::new(&mut self.matrix, lines)
+  }
+
+  /// Creates an iterator over the values of the matrix and their `(row, column)` index.
+  pub fn indexed_iter(&self) -> IndexedIterator<'_, T> {
+
+    self.as_view().indexed_iter()
+  }
+
+  /// Creates an iterator over the rows of the matrix as `1 x columns` views.
+  pub fn rows(&self) -> MatrixRows<'_, T> {
+
+    self.as_view().rows()
+  }
+
+  /// Creates an iterator over the columns of the matrix as `rows x 1` views.
+  pub fn cols(&self) -> MatrixCols<'_, T> {
+
+    self.as_view().cols()
+  }
+
+  /// Returns the value at `(i, j)`, or `None` if the index is out of bounds.
+  pub fn get(&self, i: usize, j: usize) -> Option<&T> {
+    if (i >= self.rows) || (j >= self.cols) {
+      return None;
+    }
+
+    self.matrix.get(self.offset(i, j))
+  }
+
+  /// Returns a mutable reference to the value at `(i, j)`, or `None` if the index is out of bounds.
+  pub fn get_mut(&mut self, i: usize, j: usize) -> Option<&mut T> {
+    if (i >= self.rows) || (j >= self.cols) {
+      return None;
+    }
+
+    let offset = self.offset(i, j);
+
+    self.matrix.get_mut(offset)
+  }
+}
+
+impl<T: Float> FloatMatrix<T> {
+  /// Concatenates matrices with the same number of rows from left to right, as in `[A | B]`.
+  pub fn hstack(blocks: &[&FloatMatrix<T>]) -> Result<FloatMatrix<T>, MatrixError> {
+
+    Self::from_blocks(&[blocks])
+  }
+
+  /// Concatenates matrices with the same number of columns from top to bottom.
+  pub fn vstack(blocks: &[&FloatMatrix<T>]) -> Result<FloatMatrix<T>, MatrixError> {
+    let grid: Vec<[&FloatMatrix<T>; 1]> = blocks.iter().map(|block| [*block]).collect();
+    let grid: Vec<&[&FloatMatrix<T>]> = grid.iter().map(|row| &row[..]).collect();
+
+    Self::from_blocks(&grid)
+  }
+
+  /// Builds a matrix from a grid of blocks, given as a list of block rows.
+  ///
+  /// Every block in a block row must have the same number of rows, and every block row must
+  /// have the same total number of columns.
+  pub fn from_blocks(blocks: &[&[&FloatMatrix<T>]]) -> Result<FloatMatrix<T>, MatrixError> {
+    if blocks.is_empty() || blocks.iter().any(|row| row.is_empty()) {
+      return Err(MatrixError::Empty);
+    }
+
+    let cols: usize = blocks[0].iter().map(|block| block.cols).sum();
+    let mut rows: usize = 0;
+    for block_row in blocks.iter() {
+      let height = block_row[0].rows;
+      for block in block_row.iter() {
+        if block.rows != height {
+          return Err(MatrixError::RowMismatch { expected: height, found: block.rows });
+        }
+      }
+      let width: usize = block_row.iter().map(|block| block.cols).sum();
+      if width != cols {
+        return Err(MatrixError::ColumnMismatch { expected: cols, found: width });
+      }
+      rows += height;
+    }
+
+    let mut matrix: Vec<T> = Vec::with_capacity(rows * cols);
+    for block_row in blocks.iter() {
+      for i in 0..block_row[0].rows {
+        for block in block_row.iter() {
+          for j in 0..block.cols {
+            matrix.push(block.matrix[block.offset(i, j)]);
+          }
+        }
+      }
+    }
+
+    Ok(FloatMatrix {rows, cols, layout: Layout::RowMajor, matrix})
+  }
+
+  /// Splits the matrix into a grid of blocks before each of the given row and column indices.
+  ///
+  /// The indices must be increasing and strictly inside the matrix, so that no block is empty.
+  pub fn split(&self, row_splits: &[usize], col_splits: &[usize]) -> Result<Vec<Vec<FloatMatrix<T>>>, MatrixError> {
+    let row_ranges = split_ranges(row_splits, self.rows)?;
+    let col_ranges = split_ranges(col_splits, self.cols)?;
+
+    let mut blocks: Vec<Vec<FloatMatrix<T>>> = Vec::with_capacity(row_ranges.len());
+    for (row_start, row_end) in row_ranges.iter() {
+      let mut block_row: Vec<FloatMatrix<T>> = Vec::with_capacity(col_ranges.len());
+      for (col_start, col_end) in col_ranges.iter() {
+        let view = self.view(*row_start, *col_start, row_end - row_start, col_end - col_start);
+        block_row.push(view.into());
+      }
+      blocks.push(block_row);
+    }
+
+    Ok(blocks)
+  }
+}
+
+impl<T: Float> FloatMatrix<T> {
+  /// Creates a `rows x cols` matrix from values in row-major order.
+  ///
+  /// Panics if `rows * cols` is not the length of `matrix`; see [FloatMatrix::try_from_1d_vec].
+  pub fn from_1d_vec(matrix: Vec<T>, rows: usize, cols: usize) -> Self {
+
+    Self::from_1d_vec_with_layout(matrix, rows, cols, Layout::RowMajor)
+  }
+
+  /// Creates a matrix from values stored in `layout` order, such as Fortran-ordered data with [Layout::ColMajor].
+  pub fn from_1d_vec_with_layout(matrix: Vec<T>, rows: usize, cols: usize, layout: Layout) -> Self {
+    match Self::try_from_1d_vec_with_layout(matrix, rows, cols, layout) {
+      Ok(m) => m,
+      Err(e) => panic!("{}", e)
+    }
+  }
+
+  /// Creates a `rows x cols` matrix from values in row-major order, checking that the sizes agree.
+  pub fn try_from_1d_vec(matrix: Vec<T>, rows: usize, cols: usize) -> Result<Self, MatrixError> {
+
+    Self::try_from_1d_vec_with_layout(matrix, rows, cols, Layout::RowMajor)
+  }
+
+  /// Creates a `rows x cols` matrix from values stored in the given layout, returning an error if
+  /// a dimension is zero or the sizes disagree.
+  pub fn try_from_1d_vec_with_layout(matrix: Vec<T>, rows: usize, cols: usize, layout: Layout) -> Result<Self, MatrixError> {
+    if rows < 1 || cols < 1 {
+      return Err(MatrixError::InvalidDimensions { rows, cols });
+    }
+    let size = rows.checked_mul(cols).ok_or(MatrixError::InvalidDimensions { rows, cols })?;
+    if size != matrix.len() {
+      return Err(MatrixError::SizeMismatch { expected: size, found: matrix.len() });
+    }
+
+    Ok(FloatMatrix {rows, cols, layout, matrix})
+  }
+
+  /// Converts the storage to row-major order in place, which the shape-changing methods work on.
+  fn make_row_major(&mut self) {
+    if self.layout != Layout::RowMajor {
+      self.matrix = self.values(Layout::RowMajor);
+      self.layout = Layout::RowMajor;
+    }
+  }
+
+  /// Changes the shape of the matrix to `(rows, cols)`, keeping its values in row-major order.
+  pub fn reshape(self, rows: usize, cols: usize) -> Result<FloatMatrix<T>, MatrixError> {
+    let size = rows.checked_mul(cols).ok_or(MatrixError::InvalidDimensions { rows, cols })?;
+    if size != self.matrix.len() {
+      return Err(MatrixError::SizeMismatch { expected: size, found: self.matrix.len() });
+    }
+    let matrix = self.into_values(Layout::RowMajor);
+
+    Self::try_from_1d_vec(matrix, rows, cols)
+  }
+
+  /// Resizes the matrix to `(rows, cols)`, keeping the values that still fit and setting new ones to `fill`.
+  pub fn resize(&mut self, rows: usize, cols: usize, fill: T) -> Result<(), MatrixError> {
+    if rows < 1 || cols < 1 {
+      return Err(MatrixError::InvalidDimensions { rows, cols });
+    }
+
+    let mut matrix: Vec<T> = Vec::with_capacity(rows * cols);
+    for i in 0..rows {
+      for j in 0..cols {
+        let val = if (i < self.rows) && (j < self.cols) { self.matrix[self.offset(i, j)] } else { fill };
+        matrix.push(val);
+      }
+    }
+    *self = FloatMatrix {rows, cols, layout: Layout::RowMajor, matrix};
+
+    Ok(())
+  }
+
+  /// Inserts `row` before row `idx`, shifting the rows below it down.
+  pub fn insert_row(&mut self, idx: usize, row: &[T]) -> Result<(), MatrixError> {
+    if idx > self.rows {
+      return Err(MatrixError::IndexOutOfBounds { index: idx, len: self.rows });
+    }
+    if row.len() != self.cols {
+      return Err(MatrixError::SizeMismatch { expected: self.cols, found: row.len() });
+    }
+
+    self.make_row_major();
+    let start = idx * self.cols;
+    self.matrix.splice(start..start, row.iter().copied());
+    self.rows += 1;
+
+    Ok(())
+  }
+
+  /// Inserts `col` before column `idx`, shifting the columns after it to the right.
+  pub fn insert_col(&mut self, idx: usize, col: &[T]) -> Result<(), MatrixError> {
+    if idx > self.cols {
+      return Err(MatrixError::IndexOutOfBounds { index: idx, len: self.cols });
+    }
+    if col.len() != self.rows {
+      return Err(MatrixError::SizeMismatch { expected: self.rows, found: col.len() });
+    }
+
+    let mut matrix: Vec<T> = Vec::with_capacity(self.rows * (self.cols + 1));
+    for (i, val) in col.iter().enumerate() {
+      for j in 0..idx {
+        matrix.push(self.matrix[self.offset(i, j)]);
+      }
+      matrix.push(*val);
+      for j in idx..self.cols {
+        matrix.push(self.matrix[self.offset(i, j)]);
+      }
+    }
+    *self = FloatMatrix {rows: self.rows, cols: self.cols + 1, layout: Layout::RowMajor, matrix};
+
+    Ok(())
+  }
+
+  /// Appends `row` to the bottom of the matrix.
+  pub fn push_row(&mut self, row: &[T]) -> Result<(), MatrixError> {
+
+    self.insert_row(self.rows, row)
+  }
+
+  /// Removes row `idx` and returns its values.
+  pub fn remove_row(&mut self, idx: usize) -> Result<Vec<T>, MatrixError> {
+    if idx >= self.rows {
+      return Err(MatrixError::IndexOutOfBounds { index: idx, len: self.rows });
+    }
+    if self.rows == 1 {
+      return Err(MatrixError::InvalidDimensions { rows: 0, cols: self.cols });
+    }
+
+    self.make_row_major();
+    let start = idx * self.cols;
+    let row: Vec<T> = self.matrix.drain(start..(start + self.cols)).collect();
+    self.rows -= 1;
+
+    Ok(row)
+  }
+
+  /// Removes column `idx` and returns its values.
+  pub fn remove_col(&mut self, idx: usize) -> Result<Vec<T>, MatrixError> {
+    if idx >= self.cols {
+      return Err(MatrixError::IndexOutOfBounds { index: idx, len: self.cols });
+    }
+    if self.cols == 1 {
+      return Err(MatrixError::InvalidDimensions { rows: self.rows, cols: 0 });
+    }
+
+    let mut col: Vec<T> = Vec::with_capacity(self.rows);
+    let mut matrix: Vec<T> = Vec::with_capacity(self.rows * (self.cols - 1));
+    for i in 0..self.rows {
+      for j in 0..self.cols {
+        let val = self.matrix[self.offset(i, j)];
+        if j == idx {
+          col.push(val);
+        }
+        else {
+          matrix.push(val);
+        }
+      }
+    }
+    *self = FloatMatrix {rows: self.rows, cols: self.cols - 1, layout: Layout::RowMajor, matrix};
+
+    Ok(col)
+  }
+
+  /// Swaps rows `a` and `b`. Panics if either is out of bounds.
+  pub fn swap_rows(&mut self, a: usize, b: usize) {
+    if (a >= self.rows) || (b >= self.rows) {
+      panic!("Cannot swap rows {} and {} of a matrix of size ({}, {})", a, b, self.rows, self.cols);
+    }
+
+    for j in 0..self.cols {
+      let (x, y) = (self.offset(a, j), self.offset(b, j));
+      self.matrix.swap(x, y);
+    }
+  }
+
+  /// Swaps columns `a` and `b`. Panics if either is out of bounds.
+  pub fn swap_cols(&mut self, a: usize, b: usize) {
+    if (a >= self.cols) || (b >= self.cols) {
+      panic!("Cannot swap columns {} and {} of a matrix of size ({}, {})", a, b, self.rows, self.cols);
+    }
+
+    for i in 0..self.rows {
+      let (x, y) = (self.offset(i, a), self.offset(i, b));
+      self.matrix.swap(x, y);
+    }
+  }
+}
+
+impl<T: Float> FloatMatrix<T> {
+  /// Returns the Kronecker product `A ⊗ B`, the block matrix whose block `(i, j)` is `A[(i, j)] * B`.
+  pub fn kron(&self, other: &FloatMatrix<T>) -> FloatMatrix<T> {
+    let (rows, cols) = (self.rows * other.rows, self.cols * other.cols);
+    let mut matrix: Vec<T> = Vec::with_capacity(rows * cols);
+    for i in 0..self.rows {
+      for k in 0..other.rows {
+        for j in 0..self.cols {
+          let a = self.matrix[self.offset(i, j)];
+          for l in 0..other.cols {
+            matrix.push(a * other.matrix[other.offset(k, l)]);
+          }
+        }
+      }
+    }
+
+    FloatMatrix {rows, cols, layout: Layout::RowMajor, matrix}
+  }
+
+  /// Returns the Kronecker sum `A ⊕ B = A ⊗ I + I ⊗ B` of two square matrices.
+  pub fn kron_sum(&self, other: &FloatMatrix<T>) -> Result<FloatMatrix<T>, MatrixError> {
+    if self.rows != self.cols {
+      return Err(MatrixError::NotSquare { rows: self.rows, cols: self.cols });
+    }
+    if other.rows != other.cols {
+      return Err(MatrixError::NotSquare { rows: other.rows, cols: other.cols });
+    }
+
+    let (n, m) = (self.rows, other.rows);
+    let mut matrix: Vec<T> = Vec::with_capacity(n * n * m * m);
+    for i in 0..n {
+      for k in 0..m {
+        for j in 0..n {
+          for l in 0..m {
+            let mut val = T::zero();
+            if k == l {
+              val = val + self.matrix[self.offset(i, j)];
+            }
+            if i == j {
+              val = val + other.matrix[other.offset(k, l)];
+            }
+            matrix.push(val);
+          }
+        }
+      }
+    }
+
+    Ok(FloatMatrix {rows: n * m, cols: n * m, layout: Layout::RowMajor, matrix})
+  }
+
+  /// Returns the outer product `u v^T` as a `u.len() x v.len()` matrix.
+  pub fn outer(u: &[T], v: &[T]) -> Result<FloatMatrix<T>, MatrixError> {
+    if u.is_empty() || v.is_empty() {
+      return Err(MatrixError::InvalidDimensions { rows: u.len(), cols: v.len() });
+    }
+
+    let mut matrix: Vec<T> = Vec::with_capacity(u.len() * v.len());
+    for a in u.iter() {
+      for b in v.iter() {
+        matrix.push(*a * *b);
+      }
+    }
+
+    Ok(FloatMatrix {rows: u.len(), cols: v.len(), layout: Layout::RowMajor, matrix})
+  }
+
+  /// Stacks the columns of the matrix on top of each other into a `(rows * columns) x 1` column vector.
+  pub fn vec(&self) -> FloatMatrix<T> {
+    let matrix = self.values(Layout::ColMajor);
+
+    FloatMatrix {rows: self.rows * self.cols, cols: 1, layout: Layout::RowMajor, matrix}
+  }
+
+  /// Reverses [FloatMatrix::vec], filling a `rows x cols` matrix column by column from a column vector.
+  pub fn unvec(&self, rows: usize, cols: usize) -> Result<FloatMatrix<T>, MatrixError> {
+    if self.cols != 1 {
+      return Err(MatrixError::ColumnMismatch { expected: 1, found: self.cols });
+    }
+
+    Self::try_from_1d_vec_with_layout(self.matrix.clone(), rows, cols, Layout::ColMajor)
+  }
+}
+
+impl<T: Float + MaybeSync + 'static> FloatMatrix<T> {
+  /// Multiplies two matrices whose sizes are known to be compatible.
+  fn mul_unchecked(&self, other: &FloatMatrix<T>) -> FloatMatrix<T> {
+    let mut matrix: Vec<T> = vec![T::zero(); self.rows * other.cols];
+    gemm(&self.operand(), &other.operand(), &mut matrix);
+
+    FloatMatrix {rows: self.rows, cols: other.cols, layout: Layout::RowMajor, matrix}
+  }
+
+  /// Raises a square matrix to the power `n` by repeated squaring, using `O(log n)` products.
+  ///
+  /// `pow(0)` is the identity matrix.
+  pub fn pow(&self, n: u32) -> Result<FloatMatrix<T>, MatrixError> {
+    if self.rows != self.cols {
+      return Err(MatrixError::NotSquare { rows: self.rows, cols: self.cols });
+    }
+
+    let mut result = FloatMatrix::identity(self.rows);
+    let mut base = self.clone();
+    let mut n = n;
+    while n > 0 {
+      if n & 1 == 1 {
+        result = result.mul_unchecked(&base);
+      }
+      n >>= 1;
+      if n > 0 {
+        base = base.mul_unchecked(&base);
+      }
+    }
+
+    Ok(result)
+  }
+
+  /// Multiplies two matrices with Winograd's variant of Strassen's algorithm, which replaces one
+  /// of every eight half-size products with additions and is faster for large matrices.
+  ///
+  /// Odd dimensions are padded with zeros, and blocks of 256 or less in any dimension use the
+  /// regular kernel. The result can differ from `*` in the last bits, and its error bound grows
+  /// faster with the size of the matrices.
+  pub fn mul_strassen(&self, other: &FloatMatrix<T>) -> Result<FloatMatrix<T>, MatrixError> {
+    if self.cols != other.rows {
+      return Err(MatrixError::RowMismatch { expected: self.cols, found: other.rows });
+    }
+    let matrix = strassen(&self.values(Layout::RowMajor), &other.values(Layout::RowMajor), self.rows, self.cols, other.cols);
+
+    Ok(FloatMatrix {rows: self.rows, cols: other.cols, layout: Layout::RowMajor, matrix})
+  }
+}
+
+impl<T: Float> FloatMatrix<T> {
+  /// Creates the `size x size` identity matrix.
+  pub fn identity(size: usize) -> FloatMatrix<T> {
+    let mut matrix: Vec<T> = vec![T::zero(); size * size];
     for i in 0..size {
-      identity[(i, i)] = 1.0;
+      matrix[(i * size) + i] = T::one();
     }
-    
-    identity
+
+    FloatMatrix::from_1d_vec(matrix, size, size)
+  }
+
+  /// Creates a square matrix with `diag` on the main diagonal and zeros elsewhere.
+  ///
+  /// Panics if `diag` is empty.
+  pub fn from_diagonal(diag: &[T]) -> FloatMatrix<T> {
+    let n = diag.len();
+    let mut matrix: Vec<T> = vec![T::zero(); n * n];
+    for (i, val) in diag.iter().enumerate() {
+      matrix[(i * n) + i] = *val;
+    }
+
+    FloatMatrix::from_1d_vec(matrix, n, n)
+  }
+
+  /// Returns the values on the main diagonal, `min(rows, columns)` of them.
+  pub fn diagonal(&self) -> Vec<T> {
+
+    (0..self.rows.min(self.cols)).map(|i| self.matrix[self.offset(i, i)]).collect()
+  }
+
+  /// Returns the sum of the values on the main diagonal of a square matrix.
+  pub fn trace(&self) -> Result<T, MatrixError> {
+    if !self.is_square() {
+      return Err(MatrixError::NotSquare { rows: self.rows, cols: self.cols });
+    }
+
+    Ok(self.diagonal().into_iter().fold(T::zero(), |sum, val| sum + val))
+  }
+
+  /// Returns a copy with the values above the `k`-th diagonal set to zero, keeping `(i, j)` where `j <= i + k`.
+  ///
+  /// `k = 0` is the main diagonal, `k > 0` diagonals above it and `k < 0` diagonals below it.
+  pub fn tril(&self, k: isize) -> FloatMatrix<T> {
+
+    self.keep(|i, j| (j as isize) <= (i as isize) + k)
+  }
+
+  /// Returns a copy with the values below the `k`-th diagonal set to zero, keeping `(i, j)` where `j >= i + k`.
+  pub fn triu(&self, k: isize) -> FloatMatrix<T> {
+
+    self.keep(|i, j| (j as isize) >= (i as isize) + k)
+  }
+
+  fn keep<F: Fn(usize, usize) -> bool>(&self, keep: F) -> FloatMatrix<T> {
+    let mut matrix: Vec<T> = Vec::with_capacity(self.rows * self.cols);
+    for i in 0..self.rows {
+      for j in 0..self.cols {
+        matrix.push(if keep(i, j) { self.matrix[self.offset(i, j)] } else { T::zero() });
+      }
+    }
+
+    FloatMatrix {rows: self.rows, cols: self.cols, layout: Layout::RowMajor, matrix}
+  }
+
+  /// Returns `true` if every `(i, j)` where `!keep(i, j)` holds a zero.
+  fn zero_outside<F: Fn(usize, usize) -> bool>(&self, keep: F) -> bool {
+
+    (0..self.rows).all(|i| (0..self.cols).all(|j| keep(i, j) || self.matrix[self.offset(i, j)] == T::zero()))
+  }
+
+  pub fn is_square(&self) -> bool {
+
+    self.rows == self.cols
+  }
+
+  /// Returns `true` if the matrix is square and `|A[(i, j)] - A[(j, i)]| <= tol` for all `i, j`.
+  pub fn is_symmetric(&self, tol: T) -> bool {
+    if !self.is_square() {
+      return false;
+    }
+
+    (0..self.rows).all(|i| (0..i).all(|j| (self.matrix[self.offset(i, j)] - self.matrix[self.offset(j, i)]).abs() <= tol))
+  }
+
+  /// Returns `true` if every value below the main diagonal is zero.
+  pub fn is_upper_triangular(&self) -> bool {
+
+    self.zero_outside(|i, j| j >= i)
+  }
+
+  /// Returns `true` if every value above the main diagonal is zero.
+  pub fn is_lower_triangular(&self) -> bool {
+
+    self.zero_outside(|i, j| j <= i)
+  }
+
+  /// Returns `true` if every value off the main diagonal is zero.
+  pub fn is_diagonal(&self) -> bool {
+
+    self.zero_outside(|i, j| i == j)
+  }
+
+  /// Returns `true` if every value outside the main, sub- and super-diagonals is zero.
+  pub fn is_tridiagonal(&self) -> bool {
+
+    self.zero_outside(|i, j| (j + 1 >= i) && (j <= i + 1))
+  }
+}
+
+impl<T: Float + MaybeSync + 'static> FloatMatrix<T> {
+  /// Writes `op` applied to each pair of values of `self` and `other`, which have the same size,
+  /// into the row-major `out`. Rows of two row-major matrices go through `rows` instead, which
+  /// must agree with `op`.
+  fn zip_into<F, R>(&self, other: &FloatMatrix<T>, out: &mut [T], op: F, rows: R)
+    where F: Fn(T, T) -> T + MaybeSync, R: Fn(&[T], &[T], &mut [T]) + MaybeSync {
+    let contiguous = (self.layout == Layout::RowMajor) && (other.layout == Layout::RowMajor);
+    for_each_chunk(out, self.cols, self.rows * self.cols, |i, row| {
+      if contiguous {
+        let range = (i * self.cols)..((i + 1) * self.cols);
+        rows(&self.matrix[range.clone()], &other.matrix[range], row);
+        return;
+      }
+      for (j, value) in row.iter_mut().enumerate() {
+        *value = op(self.matrix[self.offset(i, j)], other.matrix[other.offset(i, j)]);
+      }
+    });
+  }
+
+  /// Checks that `other` has the size of `self` and `out` has size `(rows, cols)`.
+  fn expect_sizes(&self, other: &FloatMatrix<T>, out: &FloatMatrix<T>, rows: usize, cols: usize) -> Result<(), MatrixError> {
+    other.expect_size(self.rows, self.cols)?;
+
+    out.expect_size(rows, cols)
+  }
+
+  /// Computes `self + other` into `out`, which must already have the same size, overwriting its
+  /// values without allocating. `out` is left in row-major order.
+  pub fn add_into(&self, other: &FloatMatrix<T>, out: &mut FloatMatrix<T>) -> Result<(), MatrixError> {
+    self.expect_sizes(other, out, self.rows, self.cols)?;
+    self.zip_into(other, &mut out.matrix, |a, b| a + b, simd::add);
+    out.layout = Layout::RowMajor;
+
+    Ok(())
+  }
+
+  /// Computes `self - other` into `out`, which must already have the same size, overwriting its
+  /// values without allocating. `out` is left in row-major order.
+  pub fn sub_into(&self, other: &FloatMatrix<T>, out: &mut FloatMatrix<T>) -> Result<(), MatrixError> {
+    self.expect_sizes(other, out, self.rows, self.cols)?;
+    self.zip_into(other, &mut out.matrix, |a, b| a - b, simd::sub);
+    out.layout = Layout::RowMajor;
+
+    Ok(())
+  }
+
+  /// Computes `self * other` into `out`, which must already have size `(self.rows, other.cols)`,
+  /// overwriting its values. `out` is left in row-major order.
+  ///
+  /// Nothing is allocated when `self` and `other` are row-major, as the operands are then read in
+  /// place instead of being packed into buffers.
+  pub fn mul_into(&self, other: &FloatMatrix<T>, out: &mut FloatMatrix<T>) -> Result<(), MatrixError> {
+    if self.cols != other.rows {
+      return Err(MatrixError::RowMismatch { expected: self.cols, found: other.rows });
+    }
+    out.expect_size(self.rows, other.cols)?;
+    gemm(&self.operand(), &other.operand(), &mut out.matrix);
+    out.layout = Layout::RowMajor;
+
+    Ok(())
   }
 }
 
 pub struct FloatMatrixIterator<'a, T: Float> {
   inner: &'a FloatMatrix<T>,
-  idx: usize
+  idx: usize,
+  end: usize
 }
 
 impl<'a, T: Float> Iterator for FloatMatrixIterator<'a, T> {
   type Item = T;
 
   fn next(&mut self) -> Option<Self::Item> {
-    if self.idx >= (self.inner.rows * self.inner.cols) {
+    if self.idx >= self.end {
       None
     }
     else {
-      let val = self.inner.matrix[self.idx as usize];
+      let val = self.inner.matrix[self.inner.position(self.idx)];
       self.idx += 1;
       Some(val)
     }
   }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+
+    (self.end - self.idx, Some(self.end - self.idx))
+  }
 }
 
-impl<T: Float> Add for FloatMatrix<T> {
+impl<'a, T: Float> DoubleEndedIterator for FloatMatrixIterator<'a, T> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    if self.idx >= self.end {
+      return None;
+    }
+
+    self.end -= 1;
+    Some(self.inner.matrix[self.inner.position(self.end)])
+  }
+}
+
+impl<'a, T: Float> ExactSizeIterator for FloatMatrixIterator<'a, T> {}
+
+impl<T: Float + MaybeSync + 'static> Add for FloatMatrix<T> {
   type Output = Result<Self, String>;
 
   fn add(self, other: Self) -> Result<Self, String> {
@@ -145,20 +1060,15 @@ impl<T: Float> Add for FloatMatrix<T> {
     }
 
     else {
-      let mut matrix_vec: Vec<T> = Vec::new();
-      for i in 0..self.rows {
-        for j in 0..self.cols {
-          let current_num = self.matrix[j + (i * self.cols)] + other.matrix[j + (i * self.cols)];
-          matrix_vec.push(current_num);
-        }
-      }
+      let mut matrix_vec: Vec<T> = vec![T::zero(); self.rows * self.cols];
+      self.zip_into(&other, &mut matrix_vec, |a, b| a + b, simd::add);
 
-      Ok(FloatMatrix {rows: self.rows, cols: self.cols, matrix: matrix_vec})
+      Ok(FloatMatrix {rows: self.rows, cols: self.cols, layout: Layout::RowMajor, matrix: matrix_vec})
     }
   }
 }
 
-impl<T: Float> Sub for FloatMatrix<T> {
+impl<T: Float + MaybeSync + 'static> Sub for FloatMatrix<T> {
   type Output = Result<Self, String>;
 
   fn sub(self, other: Self) -> Result<Self, String> {
@@ -169,20 +1079,15 @@ impl<T: Float> Sub for FloatMatrix<T> {
     }
 
     else {
-      let mut matrix_vec: Vec<T> = Vec::new();
-      for i in 0..self.rows {
-        for j in 0..self.cols {
-          let current_num = self.matrix[j + (i * self.cols)] - other.matrix[j + (i * self.cols)];
-          matrix_vec.push(current_num);
-        }
-      }
+      let mut matrix_vec: Vec<T> = vec![T::zero(); self.rows * self.cols];
+      self.zip_into(&other, &mut matrix_vec, |a, b| a - b, simd::sub);
 
-      Ok(FloatMatrix {rows: self.rows, cols: self.cols, matrix: matrix_vec})
+      Ok(FloatMatrix {rows: self.rows, cols: self.cols, layout: Layout::RowMajor, matrix: matrix_vec})
     }
   }
 }
 
-impl<T: Float> Mul for FloatMatrix<T> {
+impl<T: Float + MaybeSync + 'static> Mul for FloatMatrix<T> {
   type Output = Result<Self, String>;
 
   fn mul(self, other: Self) -> Result<Self, String> {
@@ -192,25 +1097,11 @@ impl<T: Float> Mul for FloatMatrix<T> {
       Err(s)
     }
     else {
-      let mut matrix_vec: Vec<T> = Vec::new();
-      for i in 0..self.rows {
-        for j in 0..other.cols {
-          let mut sum: T = NumCast::from(0.0).unwrap();
-          for k in 0..self.cols {
-            let current_num = self.matrix[k + (i * self.cols)] * other.matrix[j + (k * other.cols)];
-            sum = sum + current_num;
-          }
-          matrix_vec.push(sum);
-        }
-      }
-
-      Ok(FloatMatrix {rows: self.rows, cols: other.cols, matrix: matrix_vec})
+      Ok(self.mul_unchecked(&other))
     }
   } 
 }
 
-
-
 impl<T: Float + std::fmt::Display> fmt::Display for FloatMatrix<T> {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     let mut text: String = String::from("");
@@ -219,7 +1110,7 @@ impl<T: Float + std::fmt::Display> fmt::Display for FloatMatrix<T> {
     for i in 0..self.rows {
       text.push_str("[ ");
       for j in 0..self.cols {
-        let current_num = self.matrix[j + (i * self.cols)];
+        let current_num = self.matrix[self.offset(i, j)];
         let s = format!("{:>4.4} ", current_num);
         text.push_str(&s);
       }
@@ -236,7 +1127,18 @@ impl<T: Float> IntoIterator for FloatMatrix<T> {
 
   fn into_iter(self) -> Self::IntoIter {
 
-    self.matrix.into_iter()
+    self.into_values(Layout::RowMajor).into_iter()
+  }
+}
+
+impl<'a, T: Float> From<MatrixView<'a, T>> for FloatMatrix<T> {
+  fn from(view: MatrixView<'a, T>) -> Self {
+    let (rows, cols) = view.size();
+    if rows < 1 || cols < 1 {
+      panic!("Cannot initialize a matrix with rows or columns less than 1");
+    }
+
+    FloatMatrix { rows, cols, layout: Layout::RowMajor, matrix: view.to_vec() }
   }
 }
 
@@ -245,23 +1147,31 @@ impl<T: Float> Index<(usize, usize)> for FloatMatrix<T> {
 
   fn index(&self, index: (usize, usize)) -> &Self::Output {
     let (i, j) = index;
+    if (i >= self.rows) || (j >= self.cols) {
+      panic!("Index ({}, {}) is out of bounds for a matrix of size ({}, {})", i, j, self.rows, self.cols);
+    }
 
-    &self.matrix[j + (i * self.cols)]
+    &self.matrix[self.offset(i, j)]
   }
 }
 
 impl<T: Float> IndexMut<(usize, usize)> for FloatMatrix<T> {
   fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
     let (i, j) = index;
+    if (i >= self.rows) || (j >= self.cols) {
+      panic!("Index ({}, {}) is out of bounds for a matrix of size ({}, {})", i, j, self.rows, self.cols);
+    }
+
+    let offset = self.offset(i, j);
 
-    &mut self.matrix[j + (i * self.cols)]
+    &mut self.matrix[offset]
   }
 }
 
 impl<T: Float> Clone for FloatMatrix<T> {
   fn clone(&self) -> Self {
 
-    FloatMatrix {rows: self.rows, cols: self.cols, matrix: self.matrix.clone()}
+    FloatMatrix {rows: self.rows, cols: self.cols, layout: self.layout, matrix: self.matrix.clone()}
   }
 }
 
@@ -273,7 +1183,7 @@ impl From<FloatMatrix<f32>> for FloatMatrix<f64> {
       data.push(i.into());
     }
 
-    FloatMatrix { rows: m.rows, cols: m.cols, matrix: data }
+    FloatMatrix { rows: m.rows, cols: m.cols, layout: m.layout, matrix: data }
   }
 }
 
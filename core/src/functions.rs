@@ -0,0 +1,327 @@
+use num::Float;
+
+use std::fmt;
+
+use crate::{FloatMatrix, Layout, MatrixError};
+use crate::kernel::{Operand, gemm};
+use crate::parallel::{MaybeSync, for_each_chunk};
+
+/// Coefficients `b_0..=b_m` of the `[m/m]` Padé approximants to `exp` used by [FloatMatrix::expm],
+/// each paired with the largest 1-norm `θ_m` for which it is accurate to double precision.
+///
+/// See Higham, "The Scaling and Squaring Method for the Matrix Exponential Revisited" (2005).
+const PADE: [(f64, &[f64]); 4] = [
+  (1.495585217958292e-2, &[120.0, 60.0, 12.0, 1.0]),
+  (2.53939833006323e-1, &[30240.0, 15120.0, 3360.0, 420.0, 30.0, 1.0]),
+  (9.504178996162932e-1, &[17297280.0, 8648640.0, 1995840.0, 277200.0, 25200.0, 1512.0, 56.0, 1.0]),
+  (2.097847961257068e0, &[17643225600.0, 8821612800.0, 2075673600.0, 302702400.0, 30270240.0,
+                          2162160.0, 110880.0, 3960.0, 90.0, 1.0])
+];
+
+const THETA_13: f64 = 5.371920351148152e0;
+
+const PADE_13: [f64; 14] = [
+  64764752532480000.0, 32382376266240000.0, 7771770303897600.0, 1187353796428800.0,
+  129060195264000.0, 10559470521600.0, 670442572800.0, 33522128640.0, 1323241920.0,
+  40840800.0, 960960.0, 16380.0, 182.0, 1.0
+];
+
+/// Nodes and weights of the 8-point Gauss-Legendre rule on `[0, 1]`, used by [FloatMatrix::logm].
+const GAUSS_LEGENDRE: [(f64, f64); 8] = [
+  (0.019855071751231856, 0.05061426814518813),
+  (0.10166676129318664, 0.11119051722668724),
+  (0.2372337950418355, 0.15685332293894363),
+  (0.4082826787521751, 0.181341891689181),
+  (0.591717321247825, 0.181341891689181),
+  (0.7627662049581645, 0.15685332293894363),
+  (0.8983332387068134, 0.11119051722668724),
+  (0.9801449282487681, 0.05061426814518813)
+];
+
+/// The largest `||A - I||_1` for which [FloatMatrix::logm] stops taking square roots.
+const LOG_THRESHOLD: f64 = 0.25;
+
+const MAX_ITERATIONS: usize = 100;
+
+/// The LU factorization with partial pivoting of a dense row-major `n x n` matrix.
+struct Lu<T: Float> {
+  n: usize,
+  factors: Vec<T>,
+  pivots: Vec<usize>
+}
+
+impl<T: Float + MaybeSync + 'static> Lu<T> {
+  fn new(a: &[T], n: usize) -> Result<Self, MatrixError> {
+    let mut factors = a.to_vec();
+    let mut pivots: Vec<usize> = Vec::with_capacity(n);
+    for k in 0..n {
+      let mut p = k;
+      for i in (k + 1)..n {
+        if factors[(i * n) + k].abs() > factors[(p * n) + k].abs() {
+          p = i;
+        }
+      }
+      if factors[(p * n) + k] == T::zero() {
+        return Err(MatrixError::Singular);
+      }
+      pivots.push(p);
+
+      if p != k {
+        for j in 0..n {
+          factors.swap((k * n) + j, (p * n) + j);
+        }
+      }
+
+      let (above, below) = factors.split_at_mut((k + 1) * n);
+      let pivot_row = &above[(k * n)..];
+      for_each_chunk(below, n, (n - k) * (n - k), |_, row| {
+        let l = row[k] / pivot_row[k];
+        row[k] = l;
+        for j in (k + 1)..n {
+          row[j] = row[j] - (l * pivot_row[j]);
+        }
+      });
+    }
+
+    Ok(Lu { n, factors, pivots })
+  }
+
+  /// Returns `ln |det(A)|`, which unlike the determinant itself does not overflow for large `n`.
+  fn log_abs_det(&self) -> T {
+
+    (0..self.n).fold(T::zero(), |sum, i| sum + self.factors[(i * self.n) + i].abs().ln())
+  }
+
+  /// Solves `AX = B` for a row-major `n x n` matrix `B`.
+  fn solve(&self, b: &[T]) -> Vec<T> {
+    let n = self.n;
+    let mut x = b.to_vec();
+    for k in 0..n {
+      let p = self.pivots[k];
+      if p != k {
+        for j in 0..n {
+          x.swap((k * n) + j, (p * n) + j);
+        }
+      }
+      for i in (k + 1)..n {
+        let l = self.factors[(i * n) + k];
+        for j in 0..n {
+          x[(i * n) + j] = x[(i * n) + j] - (l * x[(k * n) + j]);
+        }
+      }
+    }
+
+    for i in (0..n).rev() {
+      for k in (i + 1)..n {
+        let u = self.factors[(i * n) + k];
+        for j in 0..n {
+          x[(i * n) + j] = x[(i * n) + j] - (u * x[(k * n) + j]);
+        }
+      }
+      let pivot = self.factors[(i * n) + i];
+      for j in 0..n {
+        x[(i * n) + j] = x[(i * n) + j] / pivot;
+      }
+    }
+
+    x
+  }
+
+  fn inverse(&self) -> Vec<T> {
+
+    self.solve(&identity(self.n))
+  }
+}
+
+fn identity<T: Float>(n: usize) -> Vec<T> {
+  let mut a: Vec<T> = vec![T::zero(); n * n];
+  for i in 0..n {
+    a[(i * n) + i] = T::one();
+  }
+
+  a
+}
+
+fn matmul<T: Float + MaybeSync + 'static>(a: &[T], b: &[T], n: usize) -> Vec<T> {
+  let mut c: Vec<T> = vec![T::zero(); n * n];
+  gemm(&Operand::new(a, n, n, Layout::RowMajor), &Operand::new(b, n, n, Layout::RowMajor), &mut c);
+
+  c
+}
+
+/// Returns `alpha * a + beta * b`.
+fn combine<T: Float>(alpha: T, a: &[T], beta: T, b: &[T]) -> Vec<T> {
+
+  a.iter().zip(b.iter()).map(|(x, y)| (alpha * *x) + (beta * *y)).collect()
+}
+
+/// Returns the 1-norm, the largest absolute column sum.
+fn norm1<T: Float>(a: &[T], n: usize) -> T {
+
+  (0..n).map(|j| (0..n).fold(T::zero(), |sum, i| sum + a[(i * n) + j].abs()))
+        .fold(T::zero(), T::max)
+}
+
+fn constant<T: Float>(x: f64) -> T {
+
+  T::from(x).unwrap()
+}
+
+/// Evaluates the `[m/m]` Padé approximant with coefficients `b` at `a`, for `m <= 9`.
+fn pade<T: Float + MaybeSync + 'static>(a: &[T], b: &[f64], n: usize) -> Result<Vec<T>, MatrixError> {
+  let a2 = matmul(a, a, n);
+  let mut power = identity(n);
+  let mut u_inner: Vec<T> = vec![T::zero(); n * n];
+  let mut v: Vec<T> = vec![T::zero(); n * n];
+  for k in 0..(b.len() / 2) {
+    u_inner = combine(T::one(), &u_inner, constant(b[(2 * k) + 1]), &power);
+    v = combine(T::one(), &v, constant(b[2 * k]), &power);
+    power = matmul(&power, &a2, n);
+  }
+  let u = matmul(a, &u_inner, n);
+
+  Ok(Lu::new(&combine(T::one(), &v, -T::one(), &u), n)?.solve(&combine(T::one(), &v, T::one(), &u)))
+}
+
+/// Evaluates the `[13/13]` Padé approximant at `a`, sharing `A^2`, `A^4` and `A^6` between terms.
+fn pade_13<T: Float + MaybeSync + 'static>(a: &[T], n: usize) -> Result<Vec<T>, MatrixError> {
+  let b: Vec<T> = PADE_13.iter().map(|x| constant(*x)).collect();
+  let id = identity(n);
+  let a2 = matmul(a, a, n);
+  let a4 = matmul(&a2, &a2, n);
+  let a6 = matmul(&a2, &a4, n);
+
+  let sum = |c6: T, c4: T, c2: T, c0: T| {
+    let partial = combine(c6, &a6, c4, &a4);
+    let partial = combine(T::one(), &partial, c2, &a2);
+
+    combine(T::one(), &partial, c0, &id)
+  };
+
+  let u_high = matmul(&a6, &combine(T::one(), &combine(b[13], &a6, b[11], &a4), b[9], &a2), n);
+  let u = matmul(a, &combine(T::one(), &u_high, T::one(), &sum(b[7], b[5], b[3], b[1])), n);
+
+  let v_high = matmul(&a6, &combine(T::one(), &combine(b[12], &a6, b[10], &a4), b[8], &a2), n);
+  let v = combine(T::one(), &v_high, T::one(), &sum(b[6], b[4], b[2], b[0]));
+
+  Ok(Lu::new(&combine(T::one(), &v, -T::one(), &u), n)?.solve(&combine(T::one(), &v, T::one(), &u)))
+}
+
+fn expm<T: Float + MaybeSync + 'static>(a: &[T], n: usize) -> Result<Vec<T>, MatrixError> {
+  let norm = norm1(a, n);
+  for (theta, b) in PADE.iter() {
+    if norm <= constant(*theta) {
+      return pade(a, b, n);
+    }
+  }
+
+  let s = (norm / constant(THETA_13)).log2().ceil().max(T::zero()).to_i32().unwrap_or(0);
+  let scaled: Vec<T> = a.iter().map(|x| *x * constant::<T>(2.0).powi(-s)).collect();
+  let mut x = pade_13(&scaled, n)?;
+  for _ in 0..s {
+    x = matmul(&x, &x, n);
+  }
+
+  Ok(x)
+}
+
+/// Computes the principal square root with the Denman–Beavers iteration, scaled by the
+/// determinant until the iterates are close to converging.
+fn sqrtm<T: Float + MaybeSync + 'static>(a: &[T], n: usize) -> Result<Vec<T>, MatrixError> {
+  let tol = T::epsilon() * constant(n as f64);
+  let mut y = a.to_vec();
+  let mut z = identity(n);
+  let mut delta = T::infinity();
+  for _ in 0..MAX_ITERATIONS {
+    let (y_lu, z_lu) = (Lu::new(&y, n)?, Lu::new(&z, n)?);
+    let gamma = if delta > constant(1e-2) {
+      (-(y_lu.log_abs_det() + z_lu.log_abs_det()) / constant((2 * n) as f64)).exp()
+    }
+    else {
+      T::one()
+    };
+
+    let half = constant::<T>(0.5);
+    let next_y = combine(half * gamma, &y, half / gamma, &z_lu.inverse());
+    let next_z = combine(half * gamma, &z, half / gamma, &y_lu.inverse());
+
+    let change = norm1(&combine(T::one(), &next_y, -T::one(), &y), n) / norm1(&next_y, n);
+    y = next_y;
+    z = next_z;
+    // Rounding errors can stop the change from reaching `tol`, so also stop once it stagnates.
+    if (change <= tol) || ((change < T::epsilon().sqrt()) && (change >= delta)) {
+      return Ok(y);
+    }
+    delta = change;
+  }
+
+  Err(MatrixError::NotConverged { iterations: MAX_ITERATIONS })
+}
+
+/// Computes the principal logarithm by inverse scaling and squaring: square roots are taken
+/// until `A^(1/2^k)` is close to `I`, then `log(I + X) = ∫ X (I + tX)^-1 dt` over `[0, 1]` is
+/// evaluated with Gauss-Legendre quadrature and scaled back by `2^k`.
+fn logm<T: Float + MaybeSync + 'static>(a: &[T], n: usize) -> Result<Vec<T>, MatrixError> {
+  let id = identity(n);
+  let mut x = a.to_vec();
+  let mut k = 0;
+  while norm1(&combine(T::one(), &x, -T::one(), &id), n) > constant(LOG_THRESHOLD) {
+    if k == MAX_ITERATIONS {
+      return Err(MatrixError::NotConverged { iterations: k });
+    }
+    x = sqrtm(&x, n)?;
+    k += 1;
+  }
+
+  let x = combine(T::one(), &x, -T::one(), &id);
+  let mut log: Vec<T> = vec![T::zero(); n * n];
+  for (node, weight) in GAUSS_LEGENDRE.iter() {
+    let term = Lu::new(&combine(T::one(), &id, constant(*node), &x), n)?.solve(&x);
+    log = combine(T::one(), &log, constant(*weight), &term);
+  }
+  let scale = constant::<T>(2.0).powi(k as i32);
+
+  Ok(log.iter().map(|v| *v * scale).collect())
+}
+
+impl<T: Float + fmt::Debug + MaybeSync + 'static> FloatMatrix<T> {
+  /// Applies `f` to the row-major values of a square matrix.
+  fn apply_square<F>(&self, f: F) -> Result<FloatMatrix<T>, MatrixError>
+    where F: Fn(&[T], usize) -> Result<Vec<T>, MatrixError> {
+    let (rows, cols) = self.size();
+    if rows != cols {
+      return Err(MatrixError::NotSquare { rows, cols });
+    }
+    let values = f(&self.iter().collect::<Vec<T>>(), rows)?;
+
+    Ok(FloatMatrix::from_1d_vec(values, rows, cols))
+  }
+
+  /// Computes the matrix exponential `e^A` by scaling and squaring with Padé approximants of
+  /// degree 3 to 13, chosen from the 1-norm of `A`.
+  ///
+  /// For `e^(At)`, scale the values first, e.g. with `iter_mut`.
+  pub fn expm(&self) -> Result<FloatMatrix<T>, MatrixError> {
+
+    self.apply_square(expm)
+  }
+
+  /// Computes the principal matrix logarithm, the unique `X` with `e^X = A` whose eigenvalues
+  /// have imaginary parts in `(-π, π)`.
+  ///
+  /// `A` must not have eigenvalues on the closed negative real axis. A singular matrix is reported
+  /// as [MatrixError::Singular] and negative eigenvalues usually as [MatrixError::NotConverged].
+  pub fn logm(&self) -> Result<FloatMatrix<T>, MatrixError> {
+
+    self.apply_square(logm)
+  }
+
+  /// Computes the principal square root, the unique `X` with `X^2 = A` whose eigenvalues have
+  /// positive real parts, with the scaled Denman–Beavers iteration.
+  ///
+  /// `A` must not have eigenvalues on the closed negative real axis, as for [FloatMatrix::logm].
+  pub fn sqrtm(&self) -> Result<FloatMatrix<T>, MatrixError> {
+
+    self.apply_square(sqrtm)
+  }
+}
@@ -0,0 +1,85 @@
+use num::Num;
+
+use crate::layout::Layout;
+use crate::parallel::{MaybeSync, for_each_chunk};
+use crate::simd::{Block, mul_add_block};
+
+/// Rows of the left operand packed together, sized so the packed block stays in L2.
+const MC: usize = 64;
+/// Shared dimension of a packed block, sized so one packed row of each operand stays in L1.
+const KC: usize = 256;
+/// Columns of the right operand packed together, sized so the packed panel stays in L3.
+const NC: usize = 512;
+
+/// A borrowed `rows x cols` operand of a matrix product, where value `(i, j)` is stored at
+/// `i * row_stride + j * col_stride`.
+pub(crate) struct Operand<'a, T> {
+  data: &'a [T],
+  rows: usize,
+  cols: usize,
+  row_stride: usize,
+  col_stride: usize
+}
+
+impl<'a, T: Copy> Operand<'a, T> {
+  pub(crate) fn new(data: &'a [T], rows: usize, cols: usize, layout: Layout) -> Self {
+    let (row_stride, col_stride) = layout.strides(rows, cols);
+
+    Operand { data, rows, cols, row_stride, col_stride }
+  }
+
+  /// Copies the block `rows x cols` starting at `(row, col)` into `out` in row-major order.
+  fn pack(&self, row: usize, col: usize, rows: usize, cols: usize, out: &mut Vec<T>) {
+    out.clear();
+    for i in row..(row + rows) {
+      let start = i * self.row_stride;
+      if self.col_stride == 1 {
+        out.extend_from_slice(&self.data[(start + col)..(start + col + cols)]);
+      }
+      else {
+        out.extend((col..(col + cols)).map(|j| self.data[start + (j * self.col_stride)]));
+      }
+    }
+  }
+}
+
+/// Computes the product `a * b` into the row-major `out`, overwriting its values.
+///
+/// The operands are split into blocks, and each pair of blocks is multiplied by [mul_add_block],
+/// which is vectorized for `f32` and `f64`. Blocks of an operand whose rows are contiguous are
+/// read in place, so for row-major operands nothing is allocated; the others are first packed
+/// into contiguous buffers. Each value of `out` still sums its products in increasing `k`, so the
+/// result is identical to the naive triple loop. Blocks of rows of `out` are independent, so with
+/// the `parallel` feature they are computed on separate threads without changing the result.
+pub(crate) fn gemm<T: Num + Copy + MaybeSync + 'static>(a: &Operand<'_, T>, b: &Operand<'_, T>, out: &mut [T]) {
+  let (m, k, n) = (a.rows, a.cols, b.cols);
+  debug_assert!((b.rows == k) && (out.len() == m * n));
+  out.iter_mut().for_each(|x| *x = T::zero());
+
+  let mut b_panel: Vec<T> = Vec::new();
+  for jc in (0..n).step_by(NC) {
+    let nc = NC.min(n - jc);
+    for pc in (0..k).step_by(KC) {
+      let kc = KC.min(k - pc);
+      let (b_block, ldb) = if b.col_stride == 1 {
+        (&b.data[((pc * b.row_stride) + jc)..], b.row_stride)
+      }
+      else {
+        b.pack(pc, jc, kc, nc, &mut b_panel);
+        (&b_panel[..], nc)
+      };
+      for_each_chunk(out, MC * n, m * k * n, |block, out_block| {
+        let (ic, mc) = (block * MC, out_block.len() / n);
+        let mut a_block: Vec<T> = Vec::new();
+        let (a_block, lda) = if a.col_stride == 1 {
+          (&a.data[((ic * a.row_stride) + pc)..], a.row_stride)
+        }
+        else {
+          a.pack(ic, pc, mc, kc, &mut a_block);
+          (&a_block[..], kc)
+        };
+        mul_add_block(a_block, b_block, &mut out_block[jc..], Block { mr: mc, kc, nc, lda, ldb, ldc: n });
+      });
+    }
+  }
+}
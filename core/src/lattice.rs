@@ -0,0 +1,542 @@
+use num::{
+  BigInt,
+  BigRational,
+  CheckedAdd,
+  CheckedDiv,
+  CheckedMul,
+  CheckedSub,
+  Integer,
+  NumCast,
+  Signed,
+  Zero,
+  traits::{
+    CheckedNeg,
+    CheckedRem
+  }
+};
+
+use crate::{Matrix, MatrixError};
+
+/// The Hermite normal form `H` of an integer matrix `A`, with the unimodular `U` where `U * A = H`.
+pub struct HermiteNormalForm<T: Integer + Clone + Copy> {
+  h: Matrix<T>,
+  u: Matrix<T>
+}
+
+/// The Smith normal form `D` of an integer matrix `A`, with the unimodular `U` and `V` where
+/// `U * A * V = D`.
+pub struct SmithNormalForm<T: Integer + Clone + Copy> {
+  d: Matrix<T>,
+  u: Matrix<T>,
+  v: Matrix<T>
+}
+
+/// The integer solutions of `Ax = b`: every solution is the particular solution plus an integer
+/// combination of the kernel basis vectors.
+pub struct DiophantineSolution<T> {
+  particular: Vec<T>,
+  kernel: Vec<Vec<T>>
+}
+
+/// A dense row-major integer matrix being transformed by unimodular row and column operations,
+/// with every operation checked for overflow.
+struct Work<T> {
+  rows: usize,
+  cols: usize,
+  data: Vec<T>
+}
+
+impl<T: Integer + Signed + CheckedAdd + CheckedSub + CheckedMul + CheckedNeg + CheckedDiv + CheckedRem + Copy> Work<T> {
+  fn identity(n: usize) -> Self {
+    let mut data: Vec<T> = vec![T::zero(); n * n];
+    for i in 0..n {
+      data[(i * n) + i] = T::one();
+    }
+
+    Work { rows: n, cols: n, data }
+  }
+
+  fn at(&self, i: usize, j: usize) -> T {
+
+    self.data[(i * self.cols) + j]
+  }
+
+  /// Replaces `(row a, row b)` with `(x * a + y * b, z * a + w * b)`.
+  fn combine_rows(&mut self, a: usize, b: usize, (x, y, z, w): (T, T, T, T)) -> Result<(), MatrixError> {
+    for j in 0..self.cols {
+      let (va, vb) = (self.at(a, j), self.at(b, j));
+      let overflow = |row| MatrixError::Overflow { row, col: j };
+      self.data[(a * self.cols) + j] = lincomb(x, va, y, vb).ok_or(overflow(a))?;
+      self.data[(b * self.cols) + j] = lincomb(z, va, w, vb).ok_or(overflow(b))?;
+    }
+
+    Ok(())
+  }
+
+  /// Replaces `(column a, column b)` with `(x * a + y * b, z * a + w * b)`.
+  fn combine_cols(&mut self, a: usize, b: usize, (x, y, z, w): (T, T, T, T)) -> Result<(), MatrixError> {
+    for i in 0..self.rows {
+      let (va, vb) = (self.at(i, a), self.at(i, b));
+      let overflow = |col| MatrixError::Overflow { row: i, col };
+      self.data[(i * self.cols) + a] = lincomb(x, va, y, vb).ok_or(overflow(a))?;
+      self.data[(i * self.cols) + b] = lincomb(z, va, w, vb).ok_or(overflow(b))?;
+    }
+
+    Ok(())
+  }
+
+  fn swap_rows(&mut self, a: usize, b: usize) {
+    for j in 0..self.cols {
+      self.data.swap((a * self.cols) + j, (b * self.cols) + j);
+    }
+  }
+
+  fn swap_cols(&mut self, a: usize, b: usize) {
+    for i in 0..self.rows {
+      self.data.swap((i * self.cols) + a, (i * self.cols) + b);
+    }
+  }
+
+  fn negate_row(&mut self, a: usize) -> Result<(), MatrixError> {
+    for j in 0..self.cols {
+      let idx = (a * self.cols) + j;
+      self.data[idx] = self.data[idx].checked_neg().ok_or(MatrixError::Overflow { row: a, col: j })?;
+    }
+
+    Ok(())
+  }
+
+  fn into_matrix(self) -> Matrix<T> {
+
+    Matrix::from_1d_vec(self.data, self.rows, self.cols)
+  }
+}
+
+/// Returns `x * a + y * b`, or `None` if any step overflows.
+fn lincomb<T: CheckedAdd + CheckedMul>(x: T, a: T, y: T, b: T) -> Option<T> {
+
+  x.checked_mul(&a)?.checked_add(&y.checked_mul(&b)?)
+}
+
+/// Returns whether `a` divides `b`. `T::MIN % -1` overflows, but `T::MIN` is still a multiple of `-1`.
+fn divides<T: Integer + CheckedRem + Copy>(a: T, b: T) -> bool {
+  if a.is_zero() {
+    return b.is_zero();
+  }
+
+  b.checked_rem(&a).is_none_or(|r| r.is_zero())
+}
+
+/// Returns `a.div_floor(b)`, or `None` if it overflows, which only happens where `a / b` does.
+fn checked_div_floor<T: Integer + CheckedDiv + Copy>(a: T, b: T) -> Option<T> {
+  a.checked_div(&b)?;
+
+  Some(a.div_floor(&b))
+}
+
+/// Returns whether `|a| < |b|`, comparing `-|a|` with `-|b|` since `|T::MIN|` overflows.
+fn smaller<T: Integer + Signed + Copy>(a: T, b: T) -> bool {
+  let neg_abs = |x: T| if x.is_positive() { -x } else { x };
+
+  neg_abs(a) > neg_abs(b)
+}
+
+/// Returns the unimodular `(x, y, z, w)` that maps `(a, b)` to `(gcd(a, b), 0)`, i.e.
+/// `x * a + y * b = gcd(a, b)` and `z * a + w * b = 0` with `x * w - y * z = 1`.
+///
+/// If `b` is already a multiple of `a`, the first value is left untouched so that repeated
+/// elimination never grows a pivot it does not need to.
+fn gcd_step<T: Integer + Signed + CheckedAdd + CheckedSub + CheckedMul + CheckedNeg + CheckedDiv + CheckedRem + Copy>(a: T, b: T) -> Option<(T, T, T, T)> {
+  if !a.is_zero() && divides(a, b) {
+    return Some((T::one(), T::zero(), b.checked_div(&a)?.checked_neg()?, T::one()));
+  }
+
+  // Extended Euclid, keeping `old_r = old_s * a + old_t * b`.
+  let (mut old_r, mut r) = (a, b);
+  let (mut old_s, mut s) = (T::one(), T::zero());
+  let (mut old_t, mut t) = (T::zero(), T::one());
+  while !r.is_zero() {
+    let q = checked_div_floor(old_r, r)?;
+    (old_r, r) = (r, old_r.checked_sub(&q.checked_mul(&r)?)?);
+    (old_s, s) = (s, old_s.checked_sub(&q.checked_mul(&s)?)?);
+    (old_t, t) = (t, old_t.checked_sub(&q.checked_mul(&t)?)?);
+  }
+  if old_r.is_negative() {
+    (old_r, old_s, old_t) = (old_r.checked_neg()?, old_s.checked_neg()?, old_t.checked_neg()?);
+  }
+
+  Some((old_s, old_t, b.checked_div(&old_r)?.checked_neg()?, a.checked_div(&old_r)?))
+}
+
+impl<T: Integer + Signed + NumCast + CheckedAdd + CheckedSub + CheckedMul + CheckedNeg + CheckedDiv + CheckedRem + Clone + Copy> Matrix<T> {
+  fn to_work(&self) -> Work<T> {
+    let (rows, cols) = self.size();
+
+    Work { rows, cols, data: self.iter().collect() }
+  }
+
+  /// Computes the row-style Hermite normal form `H` and a unimodular `U` with `U * A = H`.
+  ///
+  /// `H` is in row echelon form: the first non-zero value of each row, its pivot, is positive and
+  /// lies strictly right of the pivot above it, the values below each pivot are zero and the
+  /// values above it lie in `0..pivot`. Zero rows come last.
+  ///
+  /// The arithmetic is checked, and [MatrixError::Overflow] reports the position of the value
+  /// that overflowed in the matrix being updated.
+  pub fn hermite_normal_form(&self) -> Result<HermiteNormalForm<T>, MatrixError> {
+    let mut h = self.to_work();
+    let mut u: Work<T> = Work::identity(h.rows);
+
+    let mut r = 0;
+    for c in 0..h.cols {
+      if r == h.rows {
+        break;
+      }
+
+      for i in (r + 1)..h.rows {
+        if !h.at(i, c).is_zero() {
+          let step = gcd_step(h.at(r, c), h.at(i, c)).ok_or(MatrixError::Overflow { row: r, col: c })?;
+          h.combine_rows(r, i, step)?;
+          u.combine_rows(r, i, step)?;
+        }
+      }
+
+      let pivot = h.at(r, c);
+      if pivot.is_zero() {
+        continue;
+      }
+      if pivot.is_negative() {
+        h.negate_row(r)?;
+        u.negate_row(r)?;
+      }
+
+      let pivot = h.at(r, c);
+      for i in 0..r {
+        let q = h.at(i, c).div_floor(&pivot);
+        if !q.is_zero() {
+          let q = q.checked_neg().ok_or(MatrixError::Overflow { row: i, col: c })?;
+          h.combine_rows(i, r, (T::one(), q, T::zero(), T::one()))?;
+          u.combine_rows(i, r, (T::one(), q, T::zero(), T::one()))?;
+        }
+      }
+      r += 1;
+    }
+
+    Ok(HermiteNormalForm { h: h.into_matrix(), u: u.into_matrix() })
+  }
+
+  /// Computes the Smith normal form `D` and unimodular `U` and `V` with `U * A * V = D`.
+  ///
+  /// `D` has the same size as `A` and is zero off the main diagonal. Its diagonal values
+  /// `d_1, d_2, ...` are non-negative, the non-zero ones come first and each divides the next.
+  ///
+  /// The arithmetic is checked, and [MatrixError::Overflow] reports the position of the value
+  /// that overflowed in the matrix being updated.
+  pub fn smith_normal_form(&self) -> Result<SmithNormalForm<T>, MatrixError> {
+    let mut d = self.to_work();
+    let mut u: Work<T> = Work::identity(d.rows);
+    let mut v: Work<T> = Work::identity(d.cols);
+
+    for t in 0..d.rows.min(d.cols) {
+      // Move the smallest non-zero value of the remaining submatrix to the pivot.
+      let mut best: Option<(usize, usize)> = None;
+      for i in t..d.rows {
+        for j in t..d.cols {
+          let val = d.at(i, j);
+          if !val.is_zero() && best.is_none_or(|(bi, bj)| smaller(val, d.at(bi, bj))) {
+            best = Some((i, j));
+          }
+        }
+      }
+      let (bi, bj) = match best {
+        Some(pos) => pos,
+        None => break
+      };
+      d.swap_rows(t, bi);
+      u.swap_rows(t, bi);
+      d.swap_cols(t, bj);
+      v.swap_cols(t, bj);
+
+      loop {
+        for i in (t + 1)..d.rows {
+          if !d.at(i, t).is_zero() {
+            let step = gcd_step(d.at(t, t), d.at(i, t)).ok_or(MatrixError::Overflow { row: t, col: t })?;
+            d.combine_rows(t, i, step)?;
+            u.combine_rows(t, i, step)?;
+          }
+        }
+        for j in (t + 1)..d.cols {
+          if !d.at(t, j).is_zero() {
+            let step = gcd_step(d.at(t, t), d.at(t, j)).ok_or(MatrixError::Overflow { row: t, col: t })?;
+            d.combine_cols(t, j, step)?;
+            v.combine_cols(t, j, step)?;
+          }
+        }
+        // Clearing the row can refill the column, but only with a strictly smaller pivot.
+        if ((t + 1)..d.rows).any(|i| !d.at(i, t).is_zero()) {
+          continue;
+        }
+
+        // The pivot must divide the rest; if it does not, adding that row lowers the pivot.
+        let pivot = d.at(t, t);
+        let bad = ((t + 1)..d.rows).find(|i| ((t + 1)..d.cols).any(|j| !divides(pivot, d.at(*i, j))));
+        match bad {
+          Some(i) => {
+            d.combine_rows(t, i, (T::one(), T::one(), T::zero(), T::one()))?;
+            u.combine_rows(t, i, (T::one(), T::one(), T::zero(), T::one()))?;
+          },
+          None => break
+        }
+      }
+
+      if d.at(t, t).is_negative() {
+        d.negate_row(t)?;
+        u.negate_row(t)?;
+      }
+    }
+
+    Ok(SmithNormalForm { d: d.into_matrix(), u: u.into_matrix(), v: v.into_matrix() })
+  }
+}
+
+/// Returns `m * x`, reporting [MatrixError::Overflow] with the index of the value that overflowed.
+fn checked_mul_vec<T>(m: &Matrix<T>, x: &[T]) -> Result<Vec<T>, MatrixError>
+  where T: Integer + CheckedAdd + CheckedMul + Clone + Copy {
+  m.rows().enumerate().map(|(i, row)| {
+    row.iter().zip(x.iter()).try_fold(T::zero(), |sum, (a, b)| sum.checked_add(&a.checked_mul(b)?))
+       .ok_or(MatrixError::Overflow { row: i, col: 0 })
+  }).collect()
+}
+
+impl<T: Integer + Signed + NumCast + CheckedAdd + CheckedSub + CheckedMul + CheckedNeg + CheckedDiv + CheckedRem + Clone + Copy> Matrix<T> {
+  /// Finds the integer solutions of `Ax = b` from the Smith normal form `UAV = D`.
+  ///
+  /// Substituting `x = Vy` gives the diagonal system `Dy = Ub`, which has an integer solution
+  /// exactly when each `d_i` divides `(Ub)_i` and `(Ub)_i = 0` past the rank. The columns of `V`
+  /// past the rank then form a basis of the integer kernel of `A`.
+  ///
+  /// Returns [MatrixError::NoSolution] if there is no integer solution, even if there is a
+  /// rational one.
+  pub fn solve_diophantine(&self, b: &[T]) -> Result<DiophantineSolution<T>, MatrixError> {
+    let (rows, cols) = self.size();
+    if b.len() != rows {
+      return Err(MatrixError::SizeMismatch { expected: rows, found: b.len() });
+    }
+
+    let snf = self.smith_normal_form()?;
+    let rank = snf.rank();
+
+    let c = checked_mul_vec(&snf.u, b)?;
+    let mut y: Vec<T> = vec![T::zero(); cols];
+    for (i, val) in c.iter().enumerate() {
+      if i < rank {
+        let d = snf.d[(i, i)];
+        if !val.is_multiple_of(&d) {
+          return Err(MatrixError::NoSolution);
+        }
+        y[i] = *val / d;
+      }
+      else if !val.is_zero() {
+        return Err(MatrixError::NoSolution);
+      }
+    }
+
+    let particular = checked_mul_vec(&snf.v, &y)?;
+    let kernel = (rank..cols).map(|k| (0..cols).map(|i| snf.v[(i, k)]).collect()).collect();
+
+    Ok(DiophantineSolution { particular, kernel })
+  }
+}
+
+impl<T: Integer + Signed + NumCast + Clone + Copy> Matrix<T> {
+  /// Reduces the lattice basis given by the rows of the matrix with the Lenstra–Lenstra–Lovász
+  /// algorithm, returning a basis of the same lattice with short, nearly orthogonal rows.
+  ///
+  /// The reduced basis `b_0, b_1, ...` with Gram–Schmidt vectors `b*_i` and coefficients `μ_ij`
+  /// satisfies `|μ_ij| <= 1/2` and the Lovász condition `|b*_k|^2 >= (δ - μ_k,k-1^2) |b*_k-1|^2`.
+  /// `δ = 0.75` is the usual choice; values closer to 1 give shorter bases in more steps. All
+  /// arithmetic is done with exact big rationals, so the result does not depend on rounding.
+  ///
+  /// Returns [MatrixError::Singular] if the rows are linearly dependent, and
+  /// [MatrixError::Overflow] if a value of the reduced basis does not fit in `T`.
+  /// Panics if `δ` is not in `(0.25, 1]`.
+  pub fn lll(&self, delta: f64) -> Result<Matrix<T>, MatrixError> {
+    if !(delta > 0.25 && delta <= 1.0) {
+      panic!("LLL needs 0.25 < delta <= 1, found {}", delta);
+    }
+    let delta = BigRational::from_float(delta).unwrap();
+    let half = BigRational::new(BigInt::from(1), BigInt::from(2));
+
+    let (n, cols) = self.size();
+    let mut basis: Vec<Vec<BigInt>> = self.rows().map(|row| row.iter().map(to_big).collect()).collect();
+
+    // Gram–Schmidt orthogonalization: `mu[i][j] = <b_i, b*_j> / B_j` and `B_j = <b*_j, b*_j>`.
+    let mut mu: Vec<Vec<BigRational>> = vec![vec![BigRational::zero(); n]; n];
+    let mut norms: Vec<BigRational> = Vec::with_capacity(n);
+    let mut orthogonal: Vec<Vec<BigRational>> = Vec::with_capacity(n);
+    for i in 0..n {
+      let mut star: Vec<BigRational> = basis[i].iter().map(|x| BigRational::from_integer(x.clone())).collect();
+      for j in 0..i {
+        let dot = basis[i].iter().zip(orthogonal[j].iter())
+                          .fold(BigRational::zero(), |sum, (x, y)| sum + (y * x));
+        mu[i][j] = dot / &norms[j];
+        for (s, o) in star.iter_mut().zip(orthogonal[j].iter()) {
+          *s -= &mu[i][j] * o;
+        }
+      }
+      let norm = star.iter().fold(BigRational::zero(), |sum, x| sum + (x * x));
+      if norm.is_zero() {
+        return Err(MatrixError::Singular);
+      }
+      norms.push(norm);
+      orthogonal.push(star);
+    }
+
+    // Subtracts the nearest integer multiple of `b_l` from `b_k`, so that `|mu[k][l]| <= 1/2`.
+    let reduce = |basis: &mut Vec<Vec<BigInt>>, mu: &mut Vec<Vec<BigRational>>, k: usize, l: usize| {
+      if mu[k][l].abs() <= half {
+        return;
+      }
+      let q = mu[k][l].round();
+      let qi = q.to_integer();
+      let (lower, upper) = basis.split_at_mut(k);
+      for (x, y) in upper[0].iter_mut().zip(lower[l].iter()) {
+        *x -= &qi * y;
+      }
+      let (lower, upper) = mu.split_at_mut(k);
+      for (x, y) in upper[0].iter_mut().zip(lower[l].iter()).take(l) {
+        *x -= &q * y;
+      }
+      mu[k][l] -= &q;
+    };
+
+    let mut k = 1;
+    while k < n {
+      reduce(&mut basis, &mut mu, k, k - 1);
+
+      let m = mu[k][k - 1].clone();
+      if norms[k] >= (&delta - (&m * &m)) * &norms[k - 1] {
+        for l in (0..(k - 1)).rev() {
+          reduce(&mut basis, &mut mu, k, l);
+        }
+        k += 1;
+        continue;
+      }
+
+      // Swap `b_k` and `b_k-1` and update the Gram–Schmidt data in place (Cohen, Algorithm 2.6.3).
+      basis.swap(k, k - 1);
+      let (lower, upper) = mu.split_at_mut(k);
+      for (x, y) in lower[k - 1].iter_mut().zip(upper[0].iter_mut()).take(k - 1) {
+        std::mem::swap(x, y);
+      }
+      let norm = &norms[k] + (&m * &m * &norms[k - 1]);
+      mu[k][k - 1] = &m * &norms[k - 1] / &norm;
+      norms[k] = &norms[k - 1] * &norms[k] / &norm;
+      norms[k - 1] = norm;
+      for i in (k + 1)..n {
+        let t = mu[i][k].clone();
+        mu[i][k] = &mu[i][k - 1] - (&m * &t);
+        mu[i][k - 1] = t + (&mu[k][k - 1] * &mu[i][k]);
+      }
+      k = (k - 1).max(1);
+    }
+
+    let mut matrix: Vec<T> = Vec::with_capacity(n * cols);
+    for (i, row) in basis.into_iter().enumerate() {
+      for (j, val) in row.into_iter().enumerate() {
+        matrix.push(<T as NumCast>::from(val).ok_or(MatrixError::Overflow { row: i, col: j })?);
+      }
+    }
+
+    Ok(Matrix::from_1d_vec(matrix, n, cols))
+  }
+}
+
+fn to_big<T: Integer + NumCast + Copy>(x: T) -> BigInt {
+
+  x.to_i128().map(BigInt::from).unwrap_or_else(|| BigInt::from(x.to_u128().unwrap()))
+}
+
+impl<T: Integer + NumCast + Clone + Copy> HermiteNormalForm<T> {
+  /// Returns the Hermite normal form `H`.
+  pub fn h(&self) -> &Matrix<T> {
+
+    &self.h
+  }
+
+  /// Returns the unimodular `U` with `U * A = H`.
+  pub fn u(&self) -> &Matrix<T> {
+
+    &self.u
+  }
+
+  /// Returns the rank of `A`, the number of non-zero rows of `H`.
+  pub fn rank(&self) -> usize {
+
+    self.h.rows().filter(|row| row.iter().any(|val| !val.is_zero())).count()
+  }
+}
+
+impl<T: Integer + NumCast + Clone + Copy> SmithNormalForm<T> {
+  /// Returns the Smith normal form `D`.
+  pub fn d(&self) -> &Matrix<T> {
+
+    &self.d
+  }
+
+  /// Returns the unimodular `U` with `U * A * V = D`.
+  pub fn u(&self) -> &Matrix<T> {
+
+    &self.u
+  }
+
+  /// Returns the unimodular `V` with `U * A * V = D`.
+  pub fn v(&self) -> &Matrix<T> {
+
+    &self.v
+  }
+
+  /// Returns the invariant factors of `A`, the non-zero diagonal values of `D`.
+  pub fn invariant_factors(&self) -> Vec<T> {
+
+    self.d.diagonal().into_iter().filter(|val| !val.is_zero()).collect()
+  }
+
+  /// Returns the rank of `A`, the number of invariant factors.
+  pub fn rank(&self) -> usize {
+
+    self.invariant_factors().len()
+  }
+}
+
+impl<T: Integer + Clone + Copy> DiophantineSolution<T> {
+  /// Returns a particular solution `x_0` of `Ax = b`.
+  pub fn particular(&self) -> &[T] {
+
+    &self.particular
+  }
+
+  /// Returns a basis of the integer solutions of `Ax = 0`, empty if `x_0` is the only solution.
+  pub fn kernel(&self) -> &[Vec<T>] {
+
+    &self.kernel
+  }
+
+  /// Returns the solution `x_0 + sum(coefficients[k] * kernel[k])`.
+  ///
+  /// Panics if the number of coefficients does not match the size of the kernel basis.
+  pub fn solution(&self, coefficients: &[T]) -> Vec<T> {
+    if coefficients.len() != self.kernel.len() {
+      panic!("Expected {} coefficients, found {}", self.kernel.len(), coefficients.len());
+    }
+
+    let mut x = self.particular.clone();
+    for (coefficient, basis) in coefficients.iter().zip(self.kernel.iter()) {
+      for (val, k) in x.iter_mut().zip(basis.iter()) {
+        *val = *val + (*coefficient * *k);
+      }
+    }
+
+    x
+  }
+}
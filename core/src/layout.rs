@@ -0,0 +1,35 @@
+/// The order in which the values of a matrix are stored in memory.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Layout {
+  /// Each row is stored contiguously, as in C.
+  #[default]
+  RowMajor,
+  /// Each column is stored contiguously, as in Fortran.
+  ColMajor
+}
+
+impl Layout {
+  /// Returns the layout that stores the transpose of a matrix with the same data.
+  pub fn transpose(self) -> Layout {
+    match self {
+      Layout::RowMajor => Layout::ColMajor,
+      Layout::ColMajor => Layout::RowMajor
+    }
+  }
+
+  /// Returns the `(row, column)` strides of a `rows x cols` matrix stored in this layout.
+  pub fn strides(self, rows: usize, cols: usize) -> (usize, usize) {
+    match self {
+      Layout::RowMajor => (cols, 1),
+      Layout::ColMajor => (1, rows)
+    }
+  }
+
+  /// Returns the position of value `(i, j)` in the storage of a `rows x cols` matrix.
+  pub fn offset(self, i: usize, j: usize, rows: usize, cols: usize) -> usize {
+    let (row_stride, col_stride) = self.strides(rows, cols);
+
+    (i * row_stride) + (j * col_stride)
+  }
+}
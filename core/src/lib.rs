@@ -1,5 +1,29 @@
+pub mod banded;
+pub mod error;
+pub mod lattice;
+pub mod layout;
 pub mod matrix;
 pub mod float_matrix;
+mod functions;
+mod kernel;
+pub mod parallel;
+#[cfg(feature = "serde")]
+mod serialize;
+mod simd;
+pub mod sparse;
+pub mod static_matrix;
+mod strassen;
+pub mod vector;
+pub mod view;
 
+pub use banded::{*};
+pub use error::{*};
+pub use lattice::{*};
+pub use layout::{*};
 pub use matrix::{*};
-pub use float_matrix::{*};
\ No newline at end of file
+pub use float_matrix::{*};
+pub use parallel::{*};
+pub use sparse::{*};
+pub use static_matrix::{*};
+pub use vector::{*};
+pub use view::{*};
\ No newline at end of file
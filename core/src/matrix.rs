@@ -1,16 +1,50 @@
 use num::{
+  CheckedAdd,
+  CheckedMul,
+  CheckedSub,
   Integer,
-  NumCast
+  Num,
+  NumCast,
+  traits::{
+    SaturatingAdd,
+    SaturatingMul,
+    SaturatingSub,
+    WrappingAdd,
+    WrappingMul,
+    WrappingSub
+  }
+};
+
+use crate::error::MatrixError;
+use crate::kernel::{Operand, gemm};
+use crate::layout::Layout;
+use crate::parallel::{MaybeSync, for_each_chunk};
+use crate::simd;
+use crate::strassen::strassen;
+
+use crate::view::{
+  IndexedIterator,
+  MatrixCols,
+  MatrixIterMut,
+  MatrixRows,
+  MatrixView,
+  MatrixViewMut,
+  check_block,
+  resolve_range,
+  split_ranges,
+  view_range
 };
 
 use std::{
   fmt, 
+  num::Wrapping,
   ops::{
     Add,
     Sub,
     Mul,
     Index,
-    IndexMut
+    IndexMut,
+    RangeBounds
   }
 };
 
@@ -18,6 +52,7 @@ use std::{
 pub struct Matrix<T: Integer + Clone + Copy> {
   rows: usize,
   cols: usize,
+  layout: Layout,
   matrix: Vec<T>
 }
 
@@ -35,7 +70,7 @@ impl<T: Integer + NumCast + Clone + Copy> Matrix<T> {
       mat.push(NumCast::from(0).unwrap());
     }
 
-    Some(Matrix { rows: new_height, cols: new_width, matrix: mat })
+    Some(Matrix { rows: new_height, cols: new_width, layout: Layout::RowMajor, matrix: mat })
   }
 
   /// Creates a new `row x column` integer matrix with all values initalized to zero.
@@ -47,7 +82,7 @@ impl<T: Integer + NumCast + Clone + Copy> Matrix<T> {
   // Creates an iterator for the current matrix.
   pub fn iter(&self) -> MatrixIterator<T> {
 
-    MatrixIterator { inner: self, idx: 0 }
+    MatrixIterator { inner: self, idx: 0, end: self.rows * self.cols }
   }
 
   pub fn size(&self) -> (usize, usize) {
@@ -55,15 +90,20 @@ impl<T: Integer + NumCast + Clone + Copy> Matrix<T> {
     (self.rows, self.cols)
   }
 
+  /// Returns the transpose of the matrix by flipping its layout, so the values keep their order
+  /// in memory and transposing a row-major matrix gives a column-major one.
+  ///
+  /// [Matrix::into_transpose] also avoids cloning the values, and [Matrix::transpose_row_major]
+  /// reorders them instead.
   pub fn transpose(&self) -> Matrix<T> {
-    let mut trans: Vec<T> = Vec::new();
-    for i in 0..self.cols {
-      for j in 0..self.rows {
-        trans.push(self.matrix[i + (j * self.cols)]);
-      }
-    }
 
-    Matrix {rows: self.cols, cols: self.rows, matrix: trans}
+    Matrix {rows: self.cols, cols: self.rows, layout: self.layout.transpose(), matrix: self.matrix.clone()}
+  }
+
+  /// Returns the transpose of the matrix as a new row-major matrix, reordering the values.
+  pub fn transpose_row_major(&self) -> Matrix<T> {
+
+    Matrix {rows: self.cols, cols: self.rows, layout: Layout::RowMajor, matrix: self.values(Layout::ColMajor)}
   }
 
   pub fn t(&self) -> Matrix<T> {
@@ -88,41 +128,1159 @@ impl<T: Integer + NumCast + Clone + Copy> Matrix<T> {
     let rref: Vec<T> = self.matrix.clone();
     
 
-    Matrix {rows: self.rows, cols: self.cols, matrix: rref}
+    Matrix {rows: self.rows, cols: self.cols, layout: self.layout, matrix: rref}
+  }
+
+}
+
+impl<T: Integer + Clone + Copy> Matrix<T> {
+  /// Returns the position of value `(i, j)` in the underlying storage.
+  fn offset(&self, i: usize, j: usize) -> usize {
+
+    self.layout.offset(i, j, self.rows, self.cols)
+  }
+
+  /// Borrows the matrix as an operand of the multiplication kernel.
+  fn operand(&self) -> Operand<'_, T> {
+
+    Operand::new(&self.matrix, self.rows, self.cols, self.layout)
+  }
+
+  /// Returns the storage position of the `idx`-th value in row-major order.
+  fn position(&self, idx: usize) -> usize {
+    match self.layout {
+      Layout::RowMajor => idx,
+      Layout::ColMajor => self.offset(idx / self.cols, idx % self.cols)
+    }
+  }
+
+  /// Returns the order in which the values of the matrix are stored.
+  pub fn layout(&self) -> Layout {
+
+    self.layout
+  }
+
+  /// Returns the values of the matrix in the order they are stored.
+  pub fn as_slice(&self) -> &[T] {
+
+    &self.matrix
+  }
+
+  /// Transposes the matrix without copying by flipping its layout.
+  pub fn into_transpose(self) -> Matrix<T> {
+
+    Matrix {rows: self.cols, cols: self.rows, layout: self.layout.transpose(), matrix: self.matrix}
+  }
+
+  /// Transposes the matrix in place, moving its values so that it keeps its layout.
+  ///
+  /// Square matrices swap values across the diagonal. Other shapes rotate each cycle of the
+  /// permutation from its smallest position, found by walking the cycle, so nothing is allocated.
+  pub fn transpose_in_place(&mut self) {
+    let (rows, cols) = match self.layout {
+      Layout::RowMajor => (self.rows, self.cols),
+      Layout::ColMajor => (self.cols, self.rows)
+    };
+    if rows == cols {
+      for i in 0..rows {
+        for j in (i + 1)..cols {
+          self.matrix.swap((i * cols) + j, (j * rows) + i);
+        }
+      }
+    }
+    else if self.matrix.len() > 2 {
+      // The value at `idx` moves to `idx * rows mod (len - 1)`, and the first and last stay put.
+      let last = self.matrix.len() - 1;
+      let next = |idx: usize| (idx * rows) % last;
+      for start in 1..last {
+        let mut idx = next(start);
+        while idx > start {
+          idx = next(idx);
+        }
+        if idx < start {
+          continue;
+        }
+        let mut idx = next(start);
+        while idx != start {
+          self.matrix.swap(start, idx);
+          idx = next(idx);
+        }
+      }
+    }
+
+    (self.rows, self.cols) = (self.cols, self.rows);
+  }
+
+  /// Checks that the matrix has size `(rows, cols)`.
+  fn expect_size(&self, rows: usize, cols: usize) -> Result<(), MatrixError> {
+    if self.rows != rows {
+      return Err(MatrixError::RowMismatch { expected: rows, found: self.rows });
+    }
+    if self.cols != cols {
+      return Err(MatrixError::ColumnMismatch { expected: cols, found: self.cols });
+    }
+
+    Ok(())
+  }
+
+  /// Returns the length of the rows or columns along which values are stored in `layout` order.
+  fn line_len(&self, layout: Layout) -> usize {
+    match layout {
+      Layout::RowMajor => self.cols,
+      Layout::ColMajor => self.rows
+    }
+  }
+
+  /// Copies row `k` of the matrix into `line` for [Layout::RowMajor], or column `k` for [Layout::ColMajor].
+  fn copy_line(&self, layout: Layout, k: usize, line: &mut [T]) {
+    for (l, value) in line.iter_mut().enumerate() {
+      let (i, j) = match layout {
+        Layout::RowMajor => (k, l),
+        Layout::ColMajor => (l, k)
+      };
+      *value = self.matrix[self.offset(i, j)];
+    }
+  }
+
+  /// Returns a copy of the values in `layout` order, made on the current thread.
+  fn values(&self, layout: Layout) -> Vec<T> {
+    let mut matrix: Vec<T> = vec![T::zero(); self.matrix.len()];
+    for (k, line) in matrix.chunks_mut(self.line_len(layout).max(1)).enumerate() {
+      self.copy_line(layout, k, line);
+    }
+
+    matrix
+  }
+
+  /// Returns the values in `layout` order, copying only if the layout changes.
+  fn into_values(self, layout: Layout) -> Vec<T> {
+    if self.layout == layout {
+      return self.matrix;
+    }
+
+    self.values(layout)
+  }
+
+  /// Returns a copy of the matrix with its values stored in `layout` order.
+  pub fn to_layout(&self, layout: Layout) -> Matrix<T>
+    where T: MaybeSync {
+    let mut matrix: Vec<T> = vec![T::zero(); self.matrix.len()];
+    for_each_chunk(&mut matrix, self.line_len(layout), self.matrix.len(), |k, line| self.copy_line(layout, k, line));
+
+    Matrix {rows: self.rows, cols: self.cols, layout, matrix}
+  }
+
+  /// Converts the matrix to store its values in `layout` order, copying only if the layout changes.
+  pub fn into_layout(self, layout: Layout) -> Matrix<T>
+    where T: MaybeSync {
+    if self.layout == layout {
+      return self;
+    }
+
+    self.to_layout(layout)
+  }
+
+  /// Borrows the block of size `(rows, cols)` starting at `(row, col)` without copying.
+  pub fn view(&self, row: usize, col: usize, rows: usize, cols: usize) -> MatrixView<'_, T> {
+    check_block((self.rows, self.cols), row, col, rows, cols);
+    let (row_stride, col_stride) = self.layout.strides(self.rows, self.cols);
+    let range = view_range(self.offset(row, col), rows, cols, row_stride, col_stride);
+
+    MatrixView::new(&self.matrix[range], rows, cols, row_stride, col_stride)
+  }
+
+  /// Mutably borrows the block of size `(rows, cols)` starting at `(row, col)` without copying.
+  pub fn view_mut(&mut self, row: usize, col: usize, rows: usize, cols: usize) -> MatrixViewMut<'_, T> {
+    check_block((self.rows, self.cols), row, col, rows, cols);
+    let (row_stride, col_stride) = self.layout.strides(self.rows, self.cols);
+    let range = view_range(self.offset(row, col), rows, cols, row_stride, col_stride);
+
+    MatrixViewMut::new(&mut self.matrix[range], rows, cols, row_stride, col_stride)
+  }
+
+  /// Borrows row `i` as a `1 x columns` view.
+  pub fn row_view(&self, i: usize) -> MatrixView<'_, T> {
+
+    self.view(i, 0, 1, self.cols)
+  }
+
+  /// Mutably borrows row `i` as a `1 x columns` view. Works for either layout and panics if `i`
+  /// is out of bounds.
+  pub fn row_view_mut(&mut self, i: usize) -> MatrixViewMut<'_, T> {
+    let cols = self.cols;
+
+    self.view_mut(i, 0, 1, cols)
+  }
+
+  /// Borrows column `j` as a `rows x 1` view.
+  pub fn col_view(&self, j: usize) -> MatrixView<'_, T> {
+
+    self.view(0, j, self.rows, 1)
+  }
+
+  /// Mutably borrows column `j` as a `rows x 1` view. Works for either layout and panics if `j`
+  /// is out of bounds.
+  pub fn col_view_mut(&mut self, j: usize) -> MatrixViewMut<'_, T> {
+    let rows = self.rows;
+
+    self.view_mut(0, j, rows, 1)
+  }
+
+  /// Borrows the whole matrix as a view.
+  pub fn as_view(&self) -> MatrixView<'_, T> {
+
+    self.view(0, 0, self.rows, self.cols)
+  }
+
+  /// Borrows the block covered by a range of rows and a range of columns.
+  ///
+  /// `Index` can only return references into the matrix, so `m.slice(1..3, ..)` is the
+  /// equivalent of `m[(1..3, ..)]`.
+  pub fn slice<R: RangeBounds<usize>, C: RangeBounds<usize>>(&self, rows: R, cols: C) -> MatrixView<'_, T> {
+    let rows = resolve_range(rows, self.rows);
+    let cols = resolve_range(cols, self.cols);
+
+    self.view(rows.start, cols.start, rows.len(), cols.len())
+  }
+
+  /// Mutably borrows the block covered by a range of rows and a range of columns. Works for
+  /// either layout and panics if either range is out of bounds.
+  pub fn slice_mut<R: RangeBounds<usize>, C: RangeBounds<usize>>(&mut self, rows: R, cols: C) -> MatrixViewMut<'_, T> {
+    let rows = resolve_range(rows, self.rows);
+    let cols = resolve_range(cols, self.cols);
+
+    self.view_mut(rows.start, cols.start, rows.len(), cols.len())
+  }
+
+  /// Borrows row `i` as a `1 x columns` view, whatever the layout. Panics if `i` is out of bounds.
+  pub fn row(&self, i: usize) -> MatrixView<'_, T> {
+
+    self.row_view(i)
+  }
+
+  /// Mutably borrows row `i` as a `1 x columns` view, whatever the layout. Panics if `i` is out
+  /// of bounds.
+  pub fn row_mut(&mut self, i: usize) -> MatrixViewMut<'_, T> {
+
+    self.row_view_mut(i)
+  }
+
+  /// Returns row `i` as a slice, or `None` if `i` is out of bounds or the matrix is column-major,
+  /// where the values of a row are not contiguous.
+  pub fn row_slice(&self, i: usize) -> Option<&[T]> {
+    if (i >= self.rows) || (self.layout != Layout::RowMajor) {
+      return None;
+    }
+
+    Some(&self.matrix[(i * self.cols)..((i + 1) * self.cols)])
+  }
+
+  /// Returns row `i` as a mutable slice, or `None` if `i` is out of bounds or the matrix is
+  /// column-major.
+  pub fn row_slice_mut(&mut self, i: usize) -> Option<&mut [T]> {
+    if (i >= self.rows) || (self.layout != Layout::RowMajor) {
+      return None;
+    }
+
+    Some(&mut self.matrix[(i * self.cols)..((i + 1) * self.cols)])
+  }
+
+  /// Borrows column `j` as a `rows x 1` view.
+  pub fn col(&self, j: usize) -> MatrixView<'_, T> {
+
+    self.col_view(j)
+  }
+
+  /// Mutably borrows column `j` as a `rows x 1` view, whatever the layout. Panics if `j` is out
+  /// of bounds.
+  pub fn col_mut(&mut self, j: usize) -> MatrixViewMut<'_, T> {
+
+    self.col_view_mut(j)
+  }
+
+  /// Creates an iterator that yields mutable references to the values of the matrix in row-major
+  /// order, the same order as [iter](Self::iter), whatever the layout.
+  pub fn iter_mut(&mut self) -> MatrixIterMut<'_, T> {
+    let lines = match self.layout {
+      Layout::RowMajor => 1,
+      Layout::ColMajor => self.cols
+    };
+
+    MatrixIterMut::new(&mut self.matrix, lines)
+  }
+
+  /// Creates an iterator over the values of the matrix and their `(row, column)` index.
+  pub fn indexed_iter(&self) -> IndexedIterator<'_, T> {
+
+    self.as_view().indexed_iter()
+  }
+
+  /// Creates an iterator over the rows of the matrix as `1 x columns` views.
+  pub fn rows(&self) -> MatrixRows<'_, T> {
+
+    self.as_view().rows()
+  }
+
+  /// Creates an iterator over the columns of the matrix as `rows x 1` views.
+  pub fn cols(&self) -> MatrixCols<'_, T> {
+
+    self.as_view().cols()
+  }
+
+  /// Returns the value at `(i, j)`, or `None` if the index is out of bounds.
+  pub fn get(&self, i: usize, j: usize) -> Option<&T> {
+    if (i >= self.rows) || (j >= self.cols) {
+      return None;
+    }
+
+    self.matrix.get(self.offset(i, j))
+  }
+
+  /// Returns a mutable reference to the value at `(i, j)`, or `None` if the index is out of bounds.
+  pub fn get_mut(&mut self, i: usize, j: usize) -> Option<&mut T> {
+    if (i >= self.rows) || (j >= self.cols) {
+      return None;
+    }
+
+    let offset = self.offset(i, j);
+
+    self.matrix.get_mut(offset)
+  }
+}
+
+impl<T: Integer + Clone + Copy> Matrix<T> {
+  /// Concatenates matrices with the same number of rows from left to right, as in `[A | B]`.
+  pub fn hstack(blocks: &[&Matrix<T>]) -> Result<Matrix<T>, MatrixError> {
+
+    Self::from_blocks(&[blocks])
+  }
+
+  /// Concatenates matrices with the same number of columns from top to bottom.
+  pub fn vstack(blocks: &[&Matrix<T>]) -> Result<Matrix<T>, MatrixError> {
+    let grid: Vec<[&Matrix<T>; 1]> = blocks.iter().map(|block| [*block]).collect();
+    let grid: Vec<&[&Matrix<T>]> = grid.iter().map(|row| &row[..]).collect();
+
+    Self::from_blocks(&grid)
+  }
+
+  /// Builds a matrix from a grid of blocks, given as a list of block rows.
+  ///
+  /// Every block in a block row must have the same number of rows, and every block row must
+  /// have the same total number of columns.
+  pub fn from_blocks(blocks: &[&[&Matrix<T>]]) -> Result<Matrix<T>, MatrixError> {
+    if blocks.is_empty() || blocks.iter().any(|row| row.is_empty()) {
+      return Err(MatrixError::Empty);
+    }
+
+    let cols: usize = blocks[0].iter().map(|block| block.cols).sum();
+    let mut rows: usize = 0;
+    for block_row in blocks.iter() {
+      let height = block_row[0].rows;
+      for block in block_row.iter() {
+        if block.rows != height {
+          return Err(MatrixError::RowMismatch { expected: height, found: block.rows });
+        }
+      }
+      let width: usize = block_row.iter().map(|block| block.cols).sum();
+      if width != cols {
+        return Err(MatrixError::ColumnMismatch { expected: cols, found: width });
+      }
+      rows += height;
+    }
+
+    let mut matrix: Vec<T> = Vec::with_capacity(rows * cols);
+    for block_row in blocks.iter() {
+      for i in 0..block_row[0].rows {
+        for block in block_row.iter() {
+          for j in 0..block.cols {
+            matrix.push(block.matrix[block.offset(i, j)]);
+          }
+        }
+      }
+    }
+
+    Ok(Matrix {rows, cols, layout: Layout::RowMajor, matrix})
+  }
+
+  /// Splits the matrix into a grid of blocks before each of the given row and column indices.
+  ///
+  /// The indices must be increasing and strictly inside the matrix, so that no block is empty.
+  pub fn split(&self, row_splits: &[usize], col_splits: &[usize]) -> Result<Vec<Vec<Matrix<T>>>, MatrixError> {
+    let row_ranges = split_ranges(row_splits, self.rows)?;
+    let col_ranges = split_ranges(col_splits, self.cols)?;
+
+    let mut blocks: Vec<Vec<Matrix<T>>> = Vec::with_capacity(row_ranges.len());
+    for (row_start, row_end) in row_ranges.iter() {
+      let mut block_row: Vec<Matrix<T>> = Vec::with_capacity(col_ranges.len());
+      for (col_start, col_end) in col_ranges.iter() {
+        let view = self.view(*row_start, *col_start, row_end - row_start, col_end - col_start);
+        block_row.push(view.into());
+      }
+      blocks.push(block_row);
+    }
+
+    Ok(blocks)
+  }
+}
+
+impl<T: Integer + Clone + Copy> Matrix<T> {
+  /// Creates a `rows x cols` matrix from values in row-major order.
+  ///
+  /// Panics if `rows * cols` is not the length of `matrix`; see [Matrix::try_from_1d_vec].
+  pub fn from_1d_vec(matrix: Vec<T>, rows: usize, cols: usize) -> Self {
+
+    Self::from_1d_vec_with_layout(matrix, rows, cols, Layout::RowMajor)
+  }
+
+  /// Creates a matrix from values stored in `layout` order, such as Fortran-ordered data with [Layout::ColMajor].
+  pub fn from_1d_vec_with_layout(matrix: Vec<T>, rows: usize, cols: usize, layout: Layout) -> Self {
+    match Self::try_from_1d_vec_with_layout(matrix, rows, cols, layout) {
+      Ok(m) => m,
+      Err(e) => panic!("{}", e)
+    }
+  }
+
+  /// Creates a `rows x cols` matrix from values in row-major order, checking that the sizes agree.
+  pub fn try_from_1d_vec(matrix: Vec<T>, rows: usize, cols: usize) -> Result<Self, MatrixError> {
+
+    Self::try_from_1d_vec_with_layout(matrix, rows, cols, Layout::RowMajor)
+  }
+
+  /// Creates a `rows x cols` matrix from values stored in the given layout, returning an error if
+  /// a dimension is zero or the sizes disagree.
+  pub fn try_from_1d_vec_with_layout(matrix: Vec<T>, rows: usize, cols: usize, layout: Layout) -> Result<Self, MatrixError> {
+    if rows < 1 || cols < 1 {
+      return Err(MatrixError::InvalidDimensions { rows, cols });
+    }
+    let size = rows.checked_mul(cols).ok_or(MatrixError::InvalidDimensions { rows, cols })?;
+    if size != matrix.len() {
+      return Err(MatrixError::SizeMismatch { expected: size, found: matrix.len() });
+    }
+
+    Ok(Matrix {rows, cols, layout, matrix})
+  }
+
+  /// Converts the storage to row-major order in place, which the shape-changing methods work on.
+  fn make_row_major(&mut self) {
+    if self.layout != Layout::RowMajor {
+      self.matrix = self.values(Layout::RowMajor);
+      self.layout = Layout::RowMajor;
+    }
+  }
+
+  /// Changes the shape of the matrix to `(rows, cols)`, keeping its values in row-major order.
+  pub fn reshape(self, rows: usize, cols: usize) -> Result<Matrix<T>, MatrixError> {
+    let size = rows.checked_mul(cols).ok_or(MatrixError::InvalidDimensions { rows, cols })?;
+    if size != self.matrix.len() {
+      return Err(MatrixError::SizeMismatch { expected: size, found: self.matrix.len() });
+    }
+    let matrix = self.into_values(Layout::RowMajor);
+
+    Self::try_from_1d_vec(matrix, rows, cols)
+  }
+
+  /// Resizes the matrix to `(rows, cols)`, keeping the values that still fit and setting new ones to `fill`.
+  pub fn resize(&mut self, rows: usize, cols: usize, fill: T) -> Result<(), MatrixError> {
+    if rows < 1 || cols < 1 {
+      return Err(MatrixError::InvalidDimensions { rows, cols });
+    }
+
+    let mut matrix: Vec<T> = Vec::with_capacity(rows * cols);
+    for i in 0..rows {
+      for j in 0..cols {
+        let val = if (i < self.rows) && (j < self.cols) { self.matrix[self.offset(i, j)] } else { fill };
+        matrix.push(val);
+      }
+    }
+    *self = Matrix {rows, cols, layout: Layout::RowMajor, matrix};
+
+    Ok(())
+  }
+
+  /// Inserts `row` before row `idx`, shifting the rows below it down.
+  pub fn insert_row(&mut self, idx: usize, row: &[T]) -> Result<(), MatrixError> {
+    if idx > self.rows {
+      return Err(MatrixError::IndexOutOfBounds { index: idx, len: self.rows });
+    }
+    if row.len() != self.cols {
+      return Err(MatrixError::SizeMismatch { expected: self.cols, found: row.len() });
+    }
+
+    self.make_row_major();
+    let start = idx * self.cols;
+    self.matrix.splice(start..start, row.iter().copied());
+    self.rows += 1;
+
+    Ok(())
+  }
+
+  /// Inserts `col` before column `idx`, shifting the columns after it to the right.
+  pub fn insert_col(&mut self, idx: usize, col: &[T]) -> Result<(), MatrixError> {
+    if idx > self.cols {
+      return Err(MatrixError::IndexOutOfBounds { index: idx, len: self.cols });
+    }
+    if col.len() != self.rows {
+      return Err(MatrixError::SizeMismatch { expected: self.rows, found: col.len() });
+    }
+
+    let mut matrix: Vec<T> = Vec::with_capacity(self.rows * (self.cols + 1));
+    for (i, val) in col.iter().enumerate() {
+      for j in 0..idx {
+        matrix.push(self.matrix[self.offset(i, j)]);
+      }
+      matrix.push(*val);
+      for j in idx..self.cols {
+        matrix.push(self.matrix[self.offset(i, j)]);
+      }
+    }
+    *self = Matrix {rows: self.rows, cols: self.cols + 1, layout: Layout::RowMajor, matrix};
+
+    Ok(())
+  }
+
+  /// Appends `row` to the bottom of the matrix.
+  pub fn push_row(&mut self, row: &[T]) -> Result<(), MatrixError> {
+
+    self.insert_row(self.rows, row)
+  }
+
+  /// Removes row `idx` and returns its values.
+  pub fn remove_row(&mut self, idx: usize) -> Result<Vec<T>, MatrixError> {
+    if idx >= self.rows {
+      return Err(MatrixError::IndexOutOfBounds { index: idx, len: self.rows });
+    }
+    if self.rows == 1 {
+      return Err(MatrixError::InvalidDimensions { rows: 0, cols: self.cols });
+    }
+
+    self.make_row_major();
+    let start = idx * self.cols;
+    let row: Vec<T> = self.matrix.drain(start..(start + self.cols)).collect();
+    self.rows -= 1;
+
+    Ok(row)
+  }
+
+  /// Removes column `idx` and returns its values.
+  pub fn remove_col(&mut self, idx: usize) -> Result<Vec<T>, MatrixError> {
+    if idx >= self.cols {
+      return Err(MatrixError::IndexOutOfBounds { index: idx, len: self.cols });
+    }
+    if self.cols == 1 {
+      return Err(MatrixError::InvalidDimensions { rows: self.rows, cols: 0 });
+    }
+
+    let mut col: Vec<T> = Vec::with_capacity(self.rows);
+    let mut matrix: Vec<T> = Vec::with_capacity(self.rows * (self.cols - 1));
+    for i in 0..self.rows {
+      for j in 0..self.cols {
+        let val = self.matrix[self.offset(i, j)];
+        if j == idx {
+          col.push(val);
+        }
+        else {
+          matrix.push(val);
+        }
+      }
+    }
+    *self = Matrix {rows: self.rows, cols: self.cols - 1, layout: Layout::RowMajor, matrix};
+
+    Ok(col)
+  }
+
+  /// Swaps rows `a` and `b`. Panics if either is out of bounds.
+  pub fn swap_rows(&mut self, a: usize, b: usize) {
+    if (a >= self.rows) || (b >= self.rows) {
+      panic!("Cannot swap rows {} and {} of a matrix of size ({}, {})", a, b, self.rows, self.cols);
+    }
+
+    for j in 0..self.cols {
+      let (x, y) = (self.offset(a, j), self.offset(b, j));
+      self.matrix.swap(x, y);
+    }
+  }
+
+  /// Swaps columns `a` and `b`. Panics if either is out of bounds.
+  pub fn swap_cols(&mut self, a: usize, b: usize) {
+    if (a >= self.cols) || (b >= self.cols) {
+      panic!("Cannot swap columns {} and {} of a matrix of size ({}, {})", a, b, self.rows, self.cols);
+    }
+
+    for i in 0..self.rows {
+      let (x, y) = (self.offset(i, a), self.offset(i, b));
+      self.matrix.swap(x, y);
+    }
+  }
+}
+
+impl<T: Integer + Clone + Copy> Matrix<T> {
+  /// Returns the Kronecker product `A ⊗ B`, the block matrix whose block `(i, j)` is `A[(i, j)] * B`.
+  pub fn kron(&self, other: &Matrix<T>) -> Matrix<T> {
+    let (rows, cols) = (self.rows * other.rows, self.cols * other.cols);
+    let mut matrix: Vec<T> = Vec::with_capacity(rows * cols);
+    for i in 0..self.rows {
+      for k in 0..other.rows {
+        for j in 0..self.cols {
+          let a = self.matrix[self.offset(i, j)];
+          for l in 0..other.cols {
+            matrix.push(a * other.matrix[other.offset(k, l)]);
+          }
+        }
+      }
+    }
+
+    Matrix {rows, cols, layout: Layout::RowMajor, matrix}
+  }
+
+  /// Returns the Kronecker sum `A ⊕ B = A ⊗ I + I ⊗ B` of two square matrices.
+  pub fn kron_sum(&self, other: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+    if self.rows != self.cols {
+      return Err(MatrixError::NotSquare { rows: self.rows, cols: self.cols });
+    }
+    if other.rows != other.cols {
+      return Err(MatrixError::NotSquare { rows: other.rows, cols: other.cols });
+    }
+
+    let (n, m) = (self.rows, other.rows);
+    let mut matrix: Vec<T> = Vec::with_capacity(n * n * m * m);
+    for i in 0..n {
+      for k in 0..m {
+        for j in 0..n {
+          for l in 0..m {
+            let mut val = T::zero();
+            if k == l {
+              val = val + self.matrix[self.offset(i, j)];
+            }
+            if i == j {
+              val = val + other.matrix[other.offset(k, l)];
+            }
+            matrix.push(val);
+          }
+        }
+      }
+    }
+
+    Ok(Matrix {rows: n * m, cols: n * m, layout: Layout::RowMajor, matrix})
+  }
+
+  /// Returns the outer product `u v^T` as a `u.len() x v.len()` matrix.
+  pub fn outer(u: &[T], v: &[T]) -> Result<Matrix<T>, MatrixError> {
+    if u.is_empty() || v.is_empty() {
+      return Err(MatrixError::InvalidDimensions { rows: u.len(), cols: v.len() });
+    }
+
+    let mut matrix: Vec<T> = Vec::with_capacity(u.len() * v.len());
+    for a in u.iter() {
+      for b in v.iter() {
+        matrix.push(*a * *b);
+      }
+    }
+
+    Ok(Matrix {rows: u.len(), cols: v.len(), layout: Layout::RowMajor, matrix})
+  }
+
+  /// Stacks the columns of the matrix on top of each other into a `(rows * columns) x 1` column vector.
+  pub fn vec(&self) -> Matrix<T> {
+    let matrix = self.values(Layout::ColMajor);
+
+    Matrix {rows: self.rows * self.cols, cols: 1, layout: Layout::RowMajor, matrix}
+  }
+
+  /// Reverses [Matrix::vec], filling a `rows x cols` matrix column by column from a column vector.
+  pub fn unvec(&self, rows: usize, cols: usize) -> Result<Matrix<T>, MatrixError> {
+    if self.cols != 1 {
+      return Err(MatrixError::ColumnMismatch { expected: 1, found: self.cols });
+    }
+
+    Self::try_from_1d_vec_with_layout(self.matrix.clone(), rows, cols, Layout::ColMajor)
+  }
+}
+
+impl<T: Integer + Clone + Copy> Matrix<T> {
+  /// Combines the values of two matrices of the same size with `op`. A `None` from `op` is
+  /// reported as an overflow.
+  fn zip_with<F>(&self, other: &Matrix<T>, op: F) -> Result<Matrix<T>, MatrixError>
+    where F: Fn(T, T) -> Option<T> {
+    if self.rows != other.rows {
+      return Err(MatrixError::RowMismatch { expected: self.rows, found: other.rows });
+    }
+    if self.cols != other.cols {
+      return Err(MatrixError::ColumnMismatch { expected: self.cols, found: other.cols });
+    }
+
+    let mut matrix: Vec<T> = Vec::with_capacity(self.rows * self.cols);
+    for i in 0..self.rows {
+      for j in 0..self.cols {
+        let val = op(self.matrix[self.offset(i, j)], other.matrix[other.offset(i, j)])
+          .ok_or(MatrixError::Overflow { row: i, col: j })?;
+        matrix.push(val);
+      }
+    }
+
+    Ok(Matrix {rows: self.rows, cols: self.cols, layout: Layout::RowMajor, matrix})
+  }
+
+  /// Checks that `other` has as many rows as `self` has columns before calling [Matrix::mul_with].
+  fn product_with<F>(&self, other: &Matrix<T>, step: F) -> Result<Matrix<T>, MatrixError>
+    where F: Fn(T, T, T) -> Option<T> {
+    if self.cols != other.rows {
+      return Err(MatrixError::RowMismatch { expected: self.cols, found: other.rows });
+    }
+
+    self.mul_with(other, step)
+  }
+
+  /// Multiplies two matrices whose sizes are known to be compatible.
+  fn mul_unchecked(&self, other: &Matrix<T>) -> Matrix<T>
+    where T: MaybeSync + 'static {
+    let mut matrix: Vec<T> = vec![T::zero(); self.rows * other.cols];
+    gemm(&self.operand(), &other.operand(), &mut matrix);
+
+    Matrix {rows: self.rows, cols: other.cols, layout: Layout::RowMajor, matrix}
+  }
+
+  /// Multiplies two matrices whose sizes are known to be compatible, accumulating each product
+  /// `a * b` into `sum` with `step(sum, a, b)`. A `None` from `step` is reported as an overflow.
+  fn mul_with<F>(&self, other: &Matrix<T>, step: F) -> Result<Matrix<T>, MatrixError>
+    where F: Fn(T, T, T) -> Option<T> {
+    let mut matrix: Vec<T> = Vec::with_capacity(self.rows * other.cols);
+    for i in 0..self.rows {
+      for j in 0..other.cols {
+        let mut sum = T::zero();
+        for k in 0..self.cols {
+          sum = step(sum, self.matrix[self.offset(i, k)], other.matrix[other.offset(k, j)])
+            .ok_or(MatrixError::Overflow { row: i, col: j })?;
+        }
+        matrix.push(sum);
+      }
+    }
+
+    Ok(Matrix {rows: self.rows, cols: other.cols, layout: Layout::RowMajor, matrix})
+  }
+
+  /// Raises a square matrix to the power `n` by repeated squaring, multiplying with `mul`.
+  fn pow_with<F>(&self, n: u32, mul: F) -> Result<Matrix<T>, MatrixError>
+    where F: Fn(&Matrix<T>, &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+    if self.rows != self.cols {
+      return Err(MatrixError::NotSquare { rows: self.rows, cols: self.cols });
+    }
+
+    let mut result = Matrix::identity(self.rows);
+    let mut base = Matrix {rows: self.rows, cols: self.cols, layout: self.layout, matrix: self.matrix.clone()};
+    let mut n = n;
+    while n > 0 {
+      if n & 1 == 1 {
+        result = mul(&result, &base)?;
+      }
+      n >>= 1;
+      if n > 0 {
+        base = mul(&base, &base)?;
+      }
+    }
+
+    Ok(result)
+  }
+
+  /// Raises a square matrix to the power `n` by repeated squaring, using `O(log n)` products.
+  ///
+  /// `pow(0)` is the identity matrix. Like the `Mul` impl, this uses the plain integer operators,
+  /// so see [Matrix::checked_pow] if the values might overflow.
+  pub fn pow(&self, n: u32) -> Result<Matrix<T>, MatrixError>
+    where T: MaybeSync + 'static {
+
+    self.pow_with(n, |a, b| Ok(a.mul_unchecked(b)))
+  }
+
+  /// Multiplies two matrices with Winograd's variant of Strassen's algorithm, which replaces one
+  /// of every eight half-size products with additions and is faster for large matrices.
+  ///
+  /// Odd dimensions are padded with zeros, and blocks of 256 or less in any dimension use the
+  /// regular kernel. The intermediate sums and differences can leave the range of `T` even when
+  /// the product does not, such as `B12 - B11` for unsigned values, so all of the arithmetic
+  /// wraps around. The result is exact modulo `2^bits`, and so the same as with `*` whenever
+  /// that does not overflow.
+  pub fn mul_strassen(&self, other: &Matrix<T>) -> Result<Matrix<T>, MatrixError>
+    where T: MaybeSync + 'static, Wrapping<T>: Num {
+    if self.cols != other.rows {
+      return Err(MatrixError::RowMismatch { expected: self.cols, found: other.rows });
+    }
+    let wrapping = |x: &Matrix<T>| -> Vec<Wrapping<T>> { x.values(Layout::RowMajor).into_iter().map(Wrapping).collect() };
+    let product = strassen(&wrapping(self), &wrapping(other), self.rows, self.cols, other.cols);
+    let matrix = product.into_iter().map(|x| x.0).collect();
+
+    Ok(Matrix {rows: self.rows, cols: other.cols, layout: Layout::RowMajor, matrix})
+  }
+
+  /// Computes `A^n` with every value reduced modulo `m`. The results lie in `0..m`, even for
+  /// negative values.
+  ///
+  /// Any positive `m` up to `T::MAX` works: products that would overflow `T` are computed by
+  /// doubling instead, which is slower but exact.
+  ///
+  /// Returns [MatrixError::InvalidModulus] if `m` is not positive.
+  pub fn pow_mod(&self, n: u32, m: T) -> Result<Matrix<T>, MatrixError>
+    where T: CheckedMul {
+    if m <= T::zero() {
+      return Err(MatrixError::InvalidModulus);
+    }
+
+    let reduced = Matrix {
+      rows: self.rows,
+      cols: self.cols,
+      layout: self.layout,
+      matrix: self.matrix.iter().map(|x| x.mod_floor(&m)).collect()
+    };
+    let mut result = reduced.pow_with(n, |a, b| a.mul_with(b, |sum, x, y| Some(add_mod(sum, mul_mod(x, y, m), m))))?;
+    // The identity of `pow(0)` is not reduced by any product.
+    result.matrix.iter_mut().for_each(|x| *x = x.mod_floor(&m));
+
+    Ok(result)
+  }
+}
+
+/// Computes `(a + b) mod m` for `a` and `b` in `0..m` without overflowing.
+fn add_mod<T: Integer + Copy>(a: T, b: T, m: T) -> T {
+  if a >= m - b {
+    return a - (m - b);
+  }
+
+  a + b
+}
+
+/// Computes `(a * b) mod m` for `a` and `b` in `0..m`, doubling `a` once per bit of `b` if the
+/// product overflows.
+fn mul_mod<T: Integer + CheckedMul + Copy>(a: T, b: T, m: T) -> T {
+  if let Some(product) = a.checked_mul(&b) {
+    return product.mod_floor(&m);
+  }
+
+  let two = T::one() + T::one();
+  let (mut a, mut b, mut product) = (a, b, T::zero());
+  while b > T::zero() {
+    if b.is_odd() {
+      product = add_mod(product, a, m);
+    }
+    a = add_mod(a, a, m);
+    b = b / two;
+  }
+
+  product
+}
+
+impl<T: Integer + CheckedAdd + CheckedSub + CheckedMul + Clone + Copy> Matrix<T> {
+  /// Raises a square matrix to the power `n` like [Matrix::pow], returning
+  /// [MatrixError::Overflow] with the position of the first value that overflows.
+  pub fn checked_pow(&self, n: u32) -> Result<Matrix<T>, MatrixError> {
+
+    self.pow_with(n, |a, b| a.mul_with(b, |sum, x, y| sum.checked_add(&x.checked_mul(&y)?)))
+  }
+
+  /// Adds two matrices, returning [MatrixError::Overflow] with the position of the first value
+  /// that overflows.
+  pub fn checked_add(&self, other: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+
+    self.zip_with(other, |x, y| x.checked_add(&y))
+  }
+
+  /// Subtracts `other`, returning [MatrixError::Overflow] with the position of the first value
+  /// that overflows, e.g. a negative result for unsigned values.
+  pub fn checked_sub(&self, other: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+
+    self.zip_with(other, |x, y| x.checked_sub(&y))
+  }
+
+  /// Multiplies two matrices, returning [MatrixError::Overflow] with the position of the first
+  /// value whose products or running sum overflow.
+  pub fn checked_mul(&self, other: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+
+    self.product_with(other, |sum, x, y| sum.checked_add(&x.checked_mul(&y)?))
+  }
+}
+
+impl<T: Integer + WrappingAdd + WrappingSub + WrappingMul + Clone + Copy> Matrix<T> {
+  /// Adds two matrices, wrapping around at the bounds of `T`.
+  pub fn wrapping_add(&self, other: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+
+    self.zip_with(other, |x, y| Some(x.wrapping_add(&y)))
+  }
+
+  /// Subtracts `other`, wrapping around at the bounds of `T`.
+  pub fn wrapping_sub(&self, other: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+
+    self.zip_with(other, |x, y| Some(x.wrapping_sub(&y)))
+  }
+
+  /// Multiplies two matrices, wrapping around at the bounds of `T`. The result is the exact
+  /// product reduced modulo `2^bits`.
+  pub fn wrapping_mul(&self, other: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+
+    self.product_with(other, |sum, x, y| Some(sum.wrapping_add(&x.wrapping_mul(&y))))
+  }
+}
+
+impl<T: Integer + SaturatingAdd + SaturatingSub + SaturatingMul + Clone + Copy> Matrix<T> {
+  /// Adds two matrices, clamping each value to the bounds of `T`.
+  pub fn saturating_add(&self, other: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+
+    self.zip_with(other, |x, y| Some(x.saturating_add(&y)))
+  }
+
+  /// Subtracts `other`, clamping each value to the bounds of `T`, so unsigned values stop at zero.
+  pub fn saturating_sub(&self, other: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+
+    self.zip_with(other, |x, y| Some(x.saturating_sub(&y)))
   }
 
-  pub fn identity(size: usize) -> Matrix<u8> {
-    let mut identity: Matrix<u8> = Matrix::new(size, size);
-  
+  /// Multiplies two matrices, clamping each product and each step of the running sum to the
+  /// bounds of `T`.
+  ///
+  /// Because the sum saturates as it goes, a value that saturates and is then brought back by
+  /// terms of the opposite sign may differ from the clamped exact result.
+  pub fn saturating_mul(&self, other: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+
+    self.product_with(other, |sum, x, y| Some(sum.saturating_add(&x.saturating_mul(&y))))
+  }
+}
+
+impl<T: Integer + Clone + Copy> Matrix<T> {
+  /// Creates the `size x size` identity matrix.
+  pub fn identity(size: usize) -> Matrix<T> {
+    let mut matrix: Vec<T> = vec![T::zero(); size * size];
     for i in 0..size {
-      identity[(i, i)] = 1;
+      matrix[(i * size) + i] = T::one();
     }
-    
-    identity
+
+    Matrix::from_1d_vec(matrix, size, size)
+  }
+
+  /// Creates a square matrix with `diag` on the main diagonal and zeros elsewhere.
+  ///
+  /// Panics if `diag` is empty.
+  pub fn from_diagonal(diag: &[T]) -> Matrix<T> {
+    let n = diag.len();
+    let mut matrix: Vec<T> = vec![T::zero(); n * n];
+    for (i, val) in diag.iter().enumerate() {
+      matrix[(i * n) + i] = *val;
+    }
+
+    Matrix::from_1d_vec(matrix, n, n)
+  }
+
+  /// Returns the values on the main diagonal, `min(rows, columns)` of them.
+  pub fn diagonal(&self) -> Vec<T> {
+
+    (0..self.rows.min(self.cols)).map(|i| self.matrix[self.offset(i, i)]).collect()
+  }
+
+  /// Returns the sum of the values on the main diagonal of a square matrix.
+  pub fn trace(&self) -> Result<T, MatrixError> {
+    if !self.is_square() {
+      return Err(MatrixError::NotSquare { rows: self.rows, cols: self.cols });
+    }
+
+    Ok(self.diagonal().into_iter().fold(T::zero(), |sum, val| sum + val))
+  }
+
+  /// Returns a copy with the values above the `k`-th diagonal set to zero, keeping `(i, j)` where `j <= i + k`.
+  ///
+  /// `k = 0` is the main diagonal, `k > 0` diagonals above it and `k < 0` diagonals below it.
+  pub fn tril(&self, k: isize) -> Matrix<T> {
+
+    self.keep(|i, j| (j as isize) <= (i as isize) + k)
+  }
+
+  /// Returns a copy with the values below the `k`-th diagonal set to zero, keeping `(i, j)` where `j >= i + k`.
+  pub fn triu(&self, k: isize) -> Matrix<T> {
+
+    self.keep(|i, j| (j as isize) >= (i as isize) + k)
+  }
+
+  fn keep<F: Fn(usize, usize) -> bool>(&self, keep: F) -> Matrix<T> {
+    let mut matrix: Vec<T> = Vec::with_capacity(self.rows * self.cols);
+    for i in 0..self.rows {
+      for j in 0..self.cols {
+        matrix.push(if keep(i, j) { self.matrix[self.offset(i, j)] } else { T::zero() });
+      }
+    }
+
+    Matrix {rows: self.rows, cols: self.cols, layout: Layout::RowMajor, matrix}
+  }
+
+  /// Returns `true` if every `(i, j)` where `!keep(i, j)` holds a zero.
+  fn zero_outside<F: Fn(usize, usize) -> bool>(&self, keep: F) -> bool {
+
+    (0..self.rows).all(|i| (0..self.cols).all(|j| keep(i, j) || self.matrix[self.offset(i, j)] == T::zero()))
+  }
+
+  pub fn is_square(&self) -> bool {
+
+    self.rows == self.cols
+  }
+
+  /// Returns `true` if the matrix is square and equal to its transpose.
+  pub fn is_symmetric(&self) -> bool {
+    if !self.is_square() {
+      return false;
+    }
+
+    (0..self.rows).all(|i| (0..i).all(|j| self.matrix[self.offset(i, j)] == self.matrix[self.offset(j, i)]))
+  }
+
+  /// Returns `true` if every value below the main diagonal is zero.
+  pub fn is_upper_triangular(&self) -> bool {
+
+    self.zero_outside(|i, j| j >= i)
+  }
+
+  /// Returns `true` if every value above the main diagonal is zero.
+  pub fn is_lower_triangular(&self) -> bool {
+
+    self.zero_outside(|i, j| j <= i)
+  }
+
+  /// Returns `true` if every value off the main diagonal is zero.
+  pub fn is_diagonal(&self) -> bool {
+
+    self.zero_outside(|i, j| i == j)
+  }
+
+  /// Returns `true` if every value outside the main, sub- and super-diagonals is zero.
+  pub fn is_tridiagonal(&self) -> bool {
+
+    self.zero_outside(|i, j| (j + 1 >= i) && (j <= i + 1))
+  }
+}
+
+impl<T: Integer + Clone + Copy> Matrix<T> {
+  /// Multiplies two matrices, converting every value to the wider type `U` before multiplying
+  /// so that the products and sums are computed in `U`.
+  ///
+  /// `U` can be any type `T` converts to losslessly, the same pairs as the `From` conversions
+  /// between matrices, e.g. `Matrix<i8>` into `Matrix<i32>`. The sums can still overflow `U`
+  /// for very long rows, so pick a type with enough headroom for the inner dimension.
+  pub fn widening_mul<U>(&self, other: &Matrix<T>) -> Result<Matrix<U>, MatrixError>
+    where U: Integer + Clone + Copy + From<T> {
+    if self.cols != other.rows {
+      return Err(MatrixError::RowMismatch { expected: self.cols, found: other.rows });
+    }
+
+    let mut matrix: Vec<U> = Vec::with_capacity(self.rows * other.cols);
+    for i in 0..self.rows {
+      for j in 0..other.cols {
+        let mut sum = U::zero();
+        for k in 0..self.cols {
+          sum = sum + (U::from(self.matrix[self.offset(i, k)]) * U::from(other.matrix[other.offset(k, j)]));
+        }
+        matrix.push(sum);
+      }
+    }
+
+    Ok(Matrix {rows: self.rows, cols: other.cols, layout: Layout::RowMajor, matrix})
+  }
+}
+
+impl<T: Integer + Clone + Copy + MaybeSync + 'static> Matrix<T> {
+  /// Writes `op` applied to each pair of values of `self` and `other`, which have the same size,
+  /// into the row-major `out`. Rows of two row-major matrices go through `rows` instead, which
+  /// must agree with `op`.
+  fn zip_into<F, R>(&self, other: &Matrix<T>, out: &mut [T], op: F, rows: R)
+    where F: Fn(T, T) -> T + MaybeSync, R: Fn(&[T], &[T], &mut [T]) + MaybeSync {
+    let contiguous = (self.layout == Layout::RowMajor) && (other.layout == Layout::RowMajor);
+    for_each_chunk(out, self.cols, self.rows * self.cols, |i, row| {
+      if contiguous {
+        let range = (i * self.cols)..((i + 1) * self.cols);
+        rows(&self.matrix[range.clone()], &other.matrix[range], row);
+        return;
+      }
+      for (j, value) in row.iter_mut().enumerate() {
+        *value = op(self.matrix[self.offset(i, j)], other.matrix[other.offset(i, j)]);
+      }
+    });
+  }
+
+  /// Checks that `other` has the size of `self` and `out` has size `(rows, cols)`.
+  fn expect_sizes(&self, other: &Matrix<T>, out: &Matrix<T>, rows: usize, cols: usize) -> Result<(), MatrixError> {
+    other.expect_size(self.rows, self.cols)?;
+
+    out.expect_size(rows, cols)
+  }
+
+  /// Computes `self + other` into `out`, which must already have the same size, overwriting its
+  /// values without allocating. `out` is left in row-major order.
+  pub fn add_into(&self, other: &Matrix<T>, out: &mut Matrix<T>) -> Result<(), MatrixError> {
+    self.expect_sizes(other, out, self.rows, self.cols)?;
+    self.zip_into(other, &mut out.matrix, |a, b| a + b, simd::add);
+    out.layout = Layout::RowMajor;
+
+    Ok(())
+  }
+
+  /// Computes `self - other` into `out`, which must already have the same size, overwriting its
+  /// values without allocating. `out` is left in row-major order.
+  pub fn sub_into(&self, other: &Matrix<T>, out: &mut Matrix<T>) -> Result<(), MatrixError> {
+    self.expect_sizes(other, out, self.rows, self.cols)?;
+    self.zip_into(other, &mut out.matrix, |a, b| a - b, simd::sub);
+    out.layout = Layout::RowMajor;
+
+    Ok(())
   }
 
+  /// Computes `self * other` into `out`, which must already have size `(self.rows, other.cols)`,
+  /// overwriting its values. `out` is left in row-major order.
+  ///
+  /// Nothing is allocated when `self` and `other` are row-major, as the operands are then read in
+  /// place instead of being packed into buffers.
+  pub fn mul_into(&self, other: &Matrix<T>, out: &mut Matrix<T>) -> Result<(), MatrixError> {
+    if self.cols != other.rows {
+      return Err(MatrixError::RowMismatch { expected: self.cols, found: other.rows });
+    }
+    out.expect_size(self.rows, other.cols)?;
+    gemm(&self.operand(), &other.operand(), &mut out.matrix);
+    out.layout = Layout::RowMajor;
+
+    Ok(())
+  }
 }
 
 pub struct MatrixIterator<'a, T: Integer + Clone + Copy> {
   inner: &'a Matrix<T>,
-  idx: usize
+  idx: usize,
+  end: usize
 }
 
 impl<'a, T: Integer + Clone + Copy> Iterator for MatrixIterator<'a, T> {
   type Item = T;
 
   fn next(&mut self) -> Option<Self::Item> {
-    if self.idx >= (self.inner.rows * self.inner.cols) {
+    if self.idx >= self.end {
       return None;
     }
 
-    let val = self.inner.matrix[self.idx];
+    let val = self.inner.matrix[self.inner.position(self.idx)];
     self.idx += 1;
     Some(val)
   }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+
+    (self.end - self.idx, Some(self.end - self.idx))
+  }
 }
 
-impl<T: Integer + Clone + Copy> Add for Matrix<T> {
+impl<'a, T: Integer + Clone + Copy> DoubleEndedIterator for MatrixIterator<'a, T> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    if self.idx >= self.end {
+      return None;
+    }
+
+    self.end -= 1;
+    Some(self.inner.matrix[self.inner.position(self.end)])
+  }
+}
+
+impl<'a, T: Integer + Clone + Copy> ExactSizeIterator for MatrixIterator<'a, T> {}
+
+impl<T: Integer + Clone + Copy + MaybeSync + 'static> Add for Matrix<T> {
   type Output = Result<Self, String>;
 
   fn add(self, other: Self) -> Result<Self, String> {
@@ -132,19 +1290,14 @@ impl<T: Integer + Clone + Copy> Add for Matrix<T> {
       return Err(s);
     }
 
-    let mut matrix_vec: Vec<T> = Vec::new();
-    for i in 0..self.rows {
-      for j in 0..self.cols {
-        let current_num = self.matrix[j + (i * self.cols)] + other.matrix[j + (i * self.cols)];
-        matrix_vec.push(current_num);
-      }
-    }
+    let mut matrix_vec: Vec<T> = vec![T::zero(); self.rows * self.cols];
+    self.zip_into(&other, &mut matrix_vec, |a, b| a + b, simd::add);
 
-    Ok(Matrix {rows: self.rows, cols: self.cols, matrix: matrix_vec})
+    Ok(Matrix {rows: self.rows, cols: self.cols, layout: Layout::RowMajor, matrix: matrix_vec})
   }
 }
 
-impl<T: Integer + Clone + Copy> Sub for Matrix<T> {
+impl<T: Integer + Clone + Copy + MaybeSync + 'static> Sub for Matrix<T> {
   type Output = Result<Self, String>;
 
   fn sub(self, other: Self) -> Result<Self, String> {
@@ -154,19 +1307,14 @@ impl<T: Integer + Clone + Copy> Sub for Matrix<T> {
       return Err(s);
     }
 
-    let mut matrix_vec: Vec<T> = Vec::new();
-    for i in 0..self.rows {
-      for j in 0..self.cols {
-        let current_num = self.matrix[j + (i * self.cols)] - other.matrix[j + (i * self.cols)];
-        matrix_vec.push(current_num);
-      }
-    }
+    let mut matrix_vec: Vec<T> = vec![T::zero(); self.rows * self.cols];
+    self.zip_into(&other, &mut matrix_vec, |a, b| a - b, simd::sub);
 
-    Ok(Matrix {rows: self.rows, cols: self.cols, matrix: matrix_vec})
+    Ok(Matrix {rows: self.rows, cols: self.cols, layout: Layout::RowMajor, matrix: matrix_vec})
   }
 }
 
-impl<T: Integer + Clone + Copy + NumCast> Mul for Matrix<T> {
+impl<T: Integer + Clone + Copy + NumCast + MaybeSync + 'static> Mul for Matrix<T> {
   type Output = Result<Self, String>;
 
   fn mul(self, other: Self) -> Result<Self, String> {
@@ -176,19 +1324,7 @@ impl<T: Integer + Clone + Copy + NumCast> Mul for Matrix<T> {
       return Err(s);
     }
 
-    let mut matrix_vec: Vec<T> = Vec::new();
-    for i in 0..self.rows {
-      for j in 0..other.cols {
-        let mut sum: T = NumCast::from(0).unwrap();
-        for k in 0..self.cols {
-          let current_num = self.matrix[k + (i * self.cols)] * other.matrix[j + (k * other.cols)];
-          sum = sum + current_num;
-        }
-        matrix_vec.push(sum);
-      }
-    }
-
-    Ok(Matrix {rows: self.rows, cols: other.cols, matrix: matrix_vec})
+    Ok(self.mul_unchecked(&other))
   } 
 }
 
@@ -200,7 +1336,7 @@ impl<T: Integer + std::fmt::Display + Clone + Copy> fmt::Display for Matrix<T> {
     for i in 0..self.rows {
       text.push_str("[ ");
       for j in 0..self.cols {
-        let current_num = self.matrix[j + (i * self.cols)];
+        let current_num = self.matrix[self.offset(i, j)];
         let s = format!("{:>4} ", current_num);
         text.push_str(&s);
       }
@@ -217,7 +1353,18 @@ impl<T: Integer + Clone + Copy> IntoIterator for Matrix<T> {
 
   fn into_iter(self) -> Self::IntoIter {
 
-    self.matrix.into_iter()
+    self.into_values(Layout::RowMajor).into_iter()
+  }
+}
+
+impl<'a, T: Integer + Clone + Copy> From<MatrixView<'a, T>> for Matrix<T> {
+  fn from(view: MatrixView<'a, T>) -> Self {
+    let (rows, cols) = view.size();
+    if rows < 1 || cols < 1 {
+      panic!("Cannot initialize a matrix with rows or columns less than 1");
+    }
+
+    Matrix { rows, cols, layout: Layout::RowMajor, matrix: view.to_vec() }
   }
 }
 
@@ -226,16 +1373,24 @@ impl<T: Integer + Clone + Copy> Index<(usize, usize)> for Matrix<T> {
 
   fn index(&self, index: (usize, usize)) -> &Self::Output {
     let (i, j) = index;
+    if (i >= self.rows) || (j >= self.cols) {
+      panic!("Index ({}, {}) is out of bounds for a matrix of size ({}, {})", i, j, self.rows, self.cols);
+    }
 
-    &self.matrix[j + (i * self.cols)]
+    &self.matrix[self.offset(i, j)]
   }
 }
 
 impl<T: Integer + Clone + Copy> IndexMut<(usize, usize)> for Matrix<T> {
   fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
     let (i, j) = index;
+    if (i >= self.rows) || (j >= self.cols) {
+      panic!("Index ({}, {}) is out of bounds for a matrix of size ({}, {})", i, j, self.rows, self.cols);
+    }
+
+    let offset = self.offset(i, j);
 
-    &mut self.matrix[j + (i * self.cols)]
+    &mut self.matrix[offset]
   }
 }
 
@@ -250,7 +1405,7 @@ macro_rules! impl_from {
             data.push(i.into());
           }
       
-          Matrix { rows: m.rows, cols: m.cols, matrix: data }
+          Matrix { rows: m.rows, cols: m.cols, layout: m.layout, matrix: data }
         }
       }
     )*
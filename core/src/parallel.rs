@@ -0,0 +1,97 @@
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The default of [parallel_threshold], about the work of multiplying two `40 x 40` matrices.
+const DEFAULT_THRESHOLD: usize = 1 << 16;
+
+static THRESHOLD: AtomicUsize = AtomicUsize::new(DEFAULT_THRESHOLD);
+
+thread_local! {
+  /// The threshold set by [with_parallel_threshold] on this thread, if any.
+  static SCOPED: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Restores the scoped threshold of the current thread when dropped, even if the scope panics.
+struct ScopeGuard {
+  previous: Option<usize>
+}
+
+impl Drop for ScopeGuard {
+  fn drop(&mut self) {
+    SCOPED.with(|scoped| scoped.set(self.previous));
+  }
+}
+
+/// Bound on the values of matrices whose operations may be split across threads.
+///
+/// With the `parallel` feature this is `Send + Sync`, which every primitive number satisfies.
+/// Without it every type implements it, so the feature never changes which types can be used.
+#[cfg(feature = "parallel")]
+pub trait MaybeSync: Send + Sync {}
+
+#[cfg(feature = "parallel")]
+impl<T: Send + Sync> MaybeSync for T {}
+
+/// Bound on the values of matrices whose operations may be split across threads.
+///
+/// With the `parallel` feature this is `Send + Sync`, which every primitive number satisfies.
+/// Without it every type implements it, so the feature never changes which types can be used.
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSync {}
+
+#[cfg(not(feature = "parallel"))]
+impl<T> MaybeSync for T {}
+
+/// Returns the number of scalar operations above which multiplication, element-wise operations,
+/// layout conversion and elimination run on rayon's thread pool.
+///
+/// Without the `parallel` feature the threshold is still stored but everything runs on one thread.
+/// Inside [with_parallel_threshold] this returns the scoped value for the current thread.
+pub fn parallel_threshold() -> usize {
+
+  SCOPED.with(|scoped| scoped.get()).unwrap_or_else(|| THRESHOLD.load(Ordering::Relaxed))
+}
+
+/// Sets the number of scalar operations above which operations run in parallel.
+///
+/// A matrix product counts `rows * inner * cols` operations, an element-wise operation or
+/// layout conversion `rows * cols`. `0` parallelizes everything and `usize::MAX` nothing. The
+/// results are the same either way, since every value is computed in the same order.
+///
+/// This applies to every thread; see [with_parallel_threshold] to change it for one scope.
+pub fn set_parallel_threshold(work: usize) {
+
+  THRESHOLD.store(work, Ordering::Relaxed)
+}
+
+/// Runs `f` with the threshold set to `work` for operations started on the current thread,
+/// leaving the threshold of every other thread, and of this one afterwards, unchanged.
+pub fn with_parallel_threshold<R, F: FnOnce() -> R>(work: usize, f: F) -> R {
+  let _guard = ScopeGuard { previous: SCOPED.with(|scoped| scoped.replace(Some(work))) };
+
+  f()
+}
+
+/// Calls `f(idx, chunk)` for each `size`-long chunk of `out`, the last possibly shorter, on
+/// several threads if `work` reaches the threshold.
+#[cfg(feature = "parallel")]
+pub(crate) fn for_each_chunk<T, F>(out: &mut [T], size: usize, work: usize, f: F)
+  where T: MaybeSync, F: Fn(usize, &mut [T]) + MaybeSync {
+  if work >= parallel_threshold() {
+    out.par_chunks_mut(size.max(1)).enumerate().for_each(|(idx, chunk)| f(idx, chunk));
+  }
+  else {
+    out.chunks_mut(size.max(1)).enumerate().for_each(|(idx, chunk)| f(idx, chunk));
+  }
+}
+
+/// Calls `f(idx, chunk)` for each `size`-long chunk of `out`, the last possibly shorter.
+#[cfg(not(feature = "parallel"))]
+pub(crate) fn for_each_chunk<T, F>(out: &mut [T], size: usize, _work: usize, f: F)
+  where T: MaybeSync, F: Fn(usize, &mut [T]) + MaybeSync {
+
+  out.chunks_mut(size.max(1)).enumerate().for_each(|(idx, chunk)| f(idx, chunk));
+}
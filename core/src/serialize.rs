@@ -0,0 +1,59 @@
+use num::{Float, Integer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+
+use crate::float_matrix::FloatMatrix;
+use crate::layout::Layout;
+use crate::matrix::Matrix;
+
+/// The serialized form of a matrix: its shape, the order of its values and the values themselves.
+#[derive(Serialize)]
+struct Shape<'a, T> {
+  rows: usize,
+  cols: usize,
+  layout: Layout,
+  data: &'a [T]
+}
+
+/// The deserialized form of a matrix, with the layout defaulting to [Layout::RowMajor] when absent.
+#[derive(Deserialize)]
+struct OwnedShape<T> {
+  rows: usize,
+  cols: usize,
+  #[serde(default)]
+  layout: Layout,
+  data: Vec<T>
+}
+
+impl<T: Integer + Clone + Copy + Serialize> Serialize for Matrix<T> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let (rows, cols) = self.as_view().size();
+
+    Shape {rows, cols, layout: self.layout(), data: self.as_slice()}.serialize(serializer)
+  }
+}
+
+/// Fails if either dimension is zero or `rows * cols` is not the number of values.
+impl<'de, T: Integer + Clone + Copy + Deserialize<'de>> Deserialize<'de> for Matrix<T> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let OwnedShape {rows, cols, layout, data} = OwnedShape::deserialize(deserializer)?;
+
+    Matrix::try_from_1d_vec_with_layout(data, rows, cols, layout).map_err(D::Error::custom)
+  }
+}
+
+impl<T: Float + Serialize> Serialize for FloatMatrix<T> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let (rows, cols) = self.as_view().size();
+
+    Shape {rows, cols, layout: self.layout(), data: self.as_slice()}.serialize(serializer)
+  }
+}
+
+/// Fails if either dimension is zero or `rows * cols` is not the number of values.
+impl<'de, T: Float + Deserialize<'de>> Deserialize<'de> for FloatMatrix<T> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let OwnedShape {rows, cols, layout, data} = OwnedShape::deserialize(deserializer)?;
+
+    FloatMatrix::try_from_1d_vec_with_layout(data, rows, cols, layout).map_err(D::Error::custom)
+  }
+}
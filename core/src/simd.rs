@@ -0,0 +1,281 @@
+use num::Num;
+
+#[cfg(target_arch = "x86_64")]
+use std::any::TypeId;
+
+/// Number of partial sums a dot product accumulates, one per lane of an AVX register of `f32`.
+///
+/// Every path, vectorized or not, adds value `i` into partial sum `i % ACC` and combines the
+/// partial sums in the same order, so the result does not depend on the CPU.
+const ACC: usize = 8;
+
+/// Rows of the output tile a vectorized block product keeps in registers.
+#[cfg(target_arch = "x86_64")]
+const MR: usize = 4;
+
+/// Whether `T` and `U` are the same type.
+#[cfg(target_arch = "x86_64")]
+fn is<T: 'static, U: 'static>() -> bool {
+
+  TypeId::of::<T>() == TypeId::of::<U>()
+}
+
+/// Reinterprets a slice of `T` as a slice of `U`. `T` and `U` must be the same type.
+#[cfg(target_arch = "x86_64")]
+unsafe fn cast<T, U>(x: &[T]) -> &[U] {
+
+  unsafe { std::slice::from_raw_parts(x.as_ptr() as *const U, x.len()) }
+}
+
+/// Reinterprets a mutable slice of `T` as one of `U`. `T` and `U` must be the same type.
+#[cfg(target_arch = "x86_64")]
+unsafe fn cast_mut<T, U>(x: &mut [T]) -> &mut [U] {
+
+  unsafe { std::slice::from_raw_parts_mut(x.as_mut_ptr() as *mut U, x.len()) }
+}
+
+/// Reinterprets a value of `T` as one of `U`. `T` and `U` must be the same type.
+#[cfg(target_arch = "x86_64")]
+unsafe fn cast_value<T, U>(x: T) -> U {
+
+  unsafe { std::mem::transmute_copy(&x) }
+}
+
+/// Returns from the enclosing function with `$body` evaluated with `$k` bound to the fastest
+/// kernels the CPU supports, if `$t` is `f32` or `f64`. Otherwise does nothing.
+macro_rules! dispatch {
+  ($t:ty, |$k:ident| $body:expr) => {
+    #[cfg(target_arch = "x86_64")]
+    {
+      if is::<$t, f64>() {
+        // SAFETY: `$t` is `f64`, and each kernel runs only if the CPU supports its features.
+        if is_x86_feature_detected!("avx") {
+          use x86::avx_f64 as $k;
+          return unsafe { $body };
+        }
+        use x86::sse_f64 as $k;
+        return unsafe { $body };
+      }
+      if is::<$t, f32>() {
+        // SAFETY: `$t` is `f32`, and each kernel runs only if the CPU supports its features.
+        if is_x86_feature_detected!("avx") {
+          use x86::avx_f32 as $k;
+          return unsafe { $body };
+        }
+        use x86::sse_f32 as $k;
+        return unsafe { $body };
+      }
+    }
+  };
+}
+
+/// Combines the partial sums of a dot product and adds the products of the remaining values.
+fn finish<T: Num + Copy>(p: &[T; ACC], x: &[T], y: &[T]) -> T {
+  let sum = ((p[0] + p[1]) + (p[2] + p[3])) + ((p[4] + p[5]) + (p[6] + p[7]));
+
+  x.iter().zip(y.iter()).fold(sum, |sum, (a, b)| sum + (*a * *b))
+}
+
+/// Returns the dot product of `x` and `y`, which must have the same length.
+pub(crate) fn dot<T: Num + Copy + 'static>(x: &[T], y: &[T]) -> T {
+  debug_assert!(x.len() == y.len());
+  dispatch!(T, |k| cast_value(k::dot(cast(x), cast(y))));
+
+  let split = x.len() - (x.len() % ACC);
+  let mut p = [T::zero(); ACC];
+  for (xc, yc) in x[..split].chunks_exact(ACC).zip(y[..split].chunks_exact(ACC)) {
+    for (s, (a, b)) in p.iter_mut().zip(xc.iter().zip(yc.iter())) {
+      *s = *s + (*a * *b);
+    }
+  }
+
+  finish(&p, &x[split..], &y[split..])
+}
+
+/// Adds `alpha * x` to `y`, which must have the same length.
+pub(crate) fn axpy<T: Num + Copy + 'static>(alpha: T, x: &[T], y: &mut [T]) {
+  debug_assert!(x.len() == y.len());
+  dispatch!(T, |k| k::axpy(cast_value(alpha), cast(x), cast_mut(y)));
+
+  for (c, b) in y.iter_mut().zip(x.iter()) {
+    *c = *c + (alpha * *b);
+  }
+}
+
+/// Writes `x + y` into `out`, which must all have the same length.
+pub(crate) fn add<T: Num + Copy + 'static>(x: &[T], y: &[T], out: &mut [T]) {
+  debug_assert!((x.len() == y.len()) && (x.len() == out.len()));
+  dispatch!(T, |k| k::add(cast(x), cast(y), cast_mut(out)));
+
+  for (c, (a, b)) in out.iter_mut().zip(x.iter().zip(y.iter())) {
+    *c = *a + *b;
+  }
+}
+
+/// Writes `x - y` into `out`, which must all have the same length.
+pub(crate) fn sub<T: Num + Copy + 'static>(x: &[T], y: &[T], out: &mut [T]) {
+  debug_assert!((x.len() == y.len()) && (x.len() == out.len()));
+  dispatch!(T, |k| k::sub(cast(x), cast(y), cast_mut(out)));
+
+  for (c, (a, b)) in out.iter_mut().zip(x.iter().zip(y.iter())) {
+    *c = *a - *b;
+  }
+}
+
+/// The shape of a block product `c += a * b`, where `a` is `mr x kc` and `b` is `kc x nc`, all
+/// stored row by row with consecutive rows `lda`, `ldb` and `ldc` values apart.
+#[derive(Clone, Copy)]
+pub(crate) struct Block {
+  pub(crate) mr: usize,
+  pub(crate) kc: usize,
+  pub(crate) nc: usize,
+  pub(crate) lda: usize,
+  pub(crate) ldb: usize,
+  pub(crate) ldc: usize
+}
+
+/// Adds the product of the blocks at the start of `a` and `b` to the block at the start of `c`.
+///
+/// Each value of `c` adds its products in increasing `k` without fused multiply-adds, so the
+/// vectorized and scalar paths give identical results.
+pub(crate) fn mul_add_block<T: Num + Copy + 'static>(a: &[T], b: &[T], c: &mut [T], block: Block) {
+  let Block { mr, kc, nc, lda, ldb, ldc } = block;
+  let (rows, cols) = tiles(a, b, c, block);
+  for i in 0..mr {
+    let skip = if i < rows { cols } else { 0 };
+    let c_row = &mut c[((i * ldc) + skip)..((i * ldc) + nc)];
+    for (p, aip) in a[(i * lda)..((i * lda) + kc)].iter().enumerate() {
+      axpy(*aip, &b[((p * ldb) + skip)..((p * ldb) + nc)], c_row);
+    }
+  }
+}
+
+/// Computes as much of [mul_add_block] as fits in whole register tiles, returning the number of
+/// rows and columns covered.
+fn tiles<T: Num + Copy + 'static>(a: &[T], b: &[T], c: &mut [T], block: Block) -> (usize, usize) {
+  let Block { mr, kc, nc, lda, ldb, ldc } = block;
+  debug_assert!((mr == 0) || (kc == 0) || ((a.len() >= ((mr - 1) * lda) + kc) && (b.len() >= ((kc - 1) * ldb) + nc) && (c.len() >= ((mr - 1) * ldc) + nc)));
+  dispatch!(T, |k| k::tiles(cast(a), cast(b), cast_mut(c), block));
+
+  (0, 0)
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+  /// Generates the kernels for one element type and instruction set, each of which must only
+  /// be called if the CPU supports `$feature`.
+  macro_rules! kernels {
+    ($name:ident, $t:ty, $feature:literal, $lanes:literal, $zero:ident, $set1:ident, $load:ident,
+     $store:ident, $add:ident, $sub:ident, $mul:ident) => {
+      pub(super) mod $name {
+        use std::arch::x86_64::*;
+
+        use super::super::{ACC, Block, MR, finish};
+
+        const LANES: usize = $lanes;
+        /// Columns of the output tile, two registers wide.
+        const NR: usize = 2 * LANES;
+
+        #[target_feature(enable = $feature)]
+        pub(in super::super) unsafe fn dot(x: &[$t], y: &[$t]) -> $t {
+          let split = x.len() - (x.len() % ACC);
+          let (xp, yp) = (x.as_ptr(), y.as_ptr());
+          let mut acc = [$zero(); ACC / LANES];
+          for i in (0..split).step_by(ACC) {
+            for (r, s) in acc.iter_mut().enumerate() {
+              let offset = i + (r * LANES);
+              *s = $add(*s, $mul(unsafe { $load(xp.add(offset)) }, unsafe { $load(yp.add(offset)) }));
+            }
+          }
+          let mut p: [$t; ACC] = [0.0; ACC];
+          for (r, s) in acc.iter().enumerate() {
+            unsafe { $store(p.as_mut_ptr().add(r * LANES), *s) };
+          }
+
+          finish(&p, &x[split..], &y[split..])
+        }
+
+        #[target_feature(enable = $feature)]
+        pub(in super::super) unsafe fn axpy(alpha: $t, x: &[$t], y: &mut [$t]) {
+          let split = x.len() - (x.len() % LANES);
+          let (xp, yp) = (x.as_ptr(), y.as_mut_ptr());
+          let a = $set1(alpha);
+          for i in (0..split).step_by(LANES) {
+            unsafe { $store(yp.add(i), $add($load(yp.add(i)), $mul(a, $load(xp.add(i))))) };
+          }
+          for (c, b) in y[split..].iter_mut().zip(x[split..].iter()) {
+            *c += alpha * *b;
+          }
+        }
+
+        #[target_feature(enable = $feature)]
+        pub(in super::super) unsafe fn add(x: &[$t], y: &[$t], out: &mut [$t]) {
+          let split = x.len() - (x.len() % LANES);
+          let (xp, yp, op) = (x.as_ptr(), y.as_ptr(), out.as_mut_ptr());
+          for i in (0..split).step_by(LANES) {
+            unsafe { $store(op.add(i), $add($load(xp.add(i)), $load(yp.add(i)))) };
+          }
+          for (c, (a, b)) in out[split..].iter_mut().zip(x[split..].iter().zip(y[split..].iter())) {
+            *c = *a + *b;
+          }
+        }
+
+        #[target_feature(enable = $feature)]
+        pub(in super::super) unsafe fn sub(x: &[$t], y: &[$t], out: &mut [$t]) {
+          let split = x.len() - (x.len() % LANES);
+          let (xp, yp, op) = (x.as_ptr(), y.as_ptr(), out.as_mut_ptr());
+          for i in (0..split).step_by(LANES) {
+            unsafe { $store(op.add(i), $sub($load(xp.add(i)), $load(yp.add(i)))) };
+          }
+          for (c, (a, b)) in out[split..].iter_mut().zip(x[split..].iter().zip(y[split..].iter())) {
+            *c = *a - *b;
+          }
+        }
+
+        /// Computes the whole `MR x NR` tiles of a block product, keeping each tile of `c` in
+        /// registers while it sums over `k`.
+        #[target_feature(enable = $feature)]
+        pub(in super::super) unsafe fn tiles(a: &[$t], b: &[$t], c: &mut [$t], block: Block) -> (usize, usize) {
+          let Block { mr, kc, nc, lda, ldb, ldc } = block;
+          let (rows, cols) = (mr - (mr % MR), nc - (nc % NR));
+          let (ap, bp, cp) = (a.as_ptr(), b.as_ptr(), c.as_mut_ptr());
+          for i in (0..rows).step_by(MR) {
+            for j in (0..cols).step_by(NR) {
+              let mut acc = [[$zero(); 2]; MR];
+              for (r, row) in acc.iter_mut().enumerate() {
+                for (h, s) in row.iter_mut().enumerate() {
+                  *s = unsafe { $load(cp.add(((i + r) * ldc) + j + (h * LANES))) };
+                }
+              }
+              for p in 0..kc {
+                let b0 = unsafe { $load(bp.add((p * ldb) + j)) };
+                let b1 = unsafe { $load(bp.add((p * ldb) + j + LANES)) };
+                for (r, row) in acc.iter_mut().enumerate() {
+                  let air = $set1(unsafe { *ap.add(((i + r) * lda) + p) });
+                  row[0] = $add(row[0], $mul(air, b0));
+                  row[1] = $add(row[1], $mul(air, b1));
+                }
+              }
+              for (r, row) in acc.iter().enumerate() {
+                for (h, s) in row.iter().enumerate() {
+                  unsafe { $store(cp.add(((i + r) * ldc) + j + (h * LANES)), *s) };
+                }
+              }
+            }
+          }
+
+          (rows, cols)
+        }
+      }
+    };
+  }
+
+  kernels!(avx_f64, f64, "avx", 4, _mm256_setzero_pd, _mm256_set1_pd, _mm256_loadu_pd,
+           _mm256_storeu_pd, _mm256_add_pd, _mm256_sub_pd, _mm256_mul_pd);
+  kernels!(sse_f64, f64, "sse2", 2, _mm_setzero_pd, _mm_set1_pd, _mm_loadu_pd,
+           _mm_storeu_pd, _mm_add_pd, _mm_sub_pd, _mm_mul_pd);
+  kernels!(avx_f32, f32, "avx", 8, _mm256_setzero_ps, _mm256_set1_ps, _mm256_loadu_ps,
+           _mm256_storeu_ps, _mm256_add_ps, _mm256_sub_ps, _mm256_mul_ps);
+  kernels!(sse_f32, f32, "sse2", 4, _mm_setzero_ps, _mm_set1_ps, _mm_loadu_ps,
+           _mm_storeu_ps, _mm_add_ps, _mm_sub_ps, _mm_mul_ps);
+}
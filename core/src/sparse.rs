@@ -0,0 +1,612 @@
+use num::{
+  Float,
+  Num
+};
+
+use std::{
+  fmt,
+  ops::Mul
+};
+
+use crate::FloatMatrix;
+
+/// A sparse matrix in coordinate (COO) format, used to assemble values before converting
+/// to [CsrMatrix] or [CscMatrix].
+///
+/// Values pushed more than once at the same `(row, column)` are summed on conversion.
+pub struct CooMatrix<T: Num + Copy> {
+  rows: usize,
+  cols: usize,
+  row_idx: Vec<usize>,
+  col_idx: Vec<usize>,
+  values: Vec<T>
+}
+
+/// A sparse matrix in compressed sparse row (CSR) format.
+pub struct CsrMatrix<T: Num + Copy> {
+  rows: usize,
+  cols: usize,
+  row_ptr: Vec<usize>,
+  col_idx: Vec<usize>,
+  values: Vec<T>
+}
+
+/// A sparse matrix in compressed sparse column (CSC) format.
+pub struct CscMatrix<T: Num + Copy> {
+  rows: usize,
+  cols: usize,
+  col_ptr: Vec<usize>,
+  row_idx: Vec<usize>,
+  values: Vec<T>
+}
+
+/// Compresses the `(major, minor, value)` triplets into pointer, index and value arrays,
+/// sorting each lane by its minor index and summing duplicates.
+fn compress<T: Num + Copy>(lanes: usize, major: &[usize], minor: &[usize], values: &[T]) -> (Vec<usize>, Vec<usize>, Vec<T>) {
+  let mut counts: Vec<usize> = vec![0; lanes + 1];
+  for m in major.iter() {
+    counts[m + 1] += 1;
+  }
+  for l in 0..lanes {
+    counts[l + 1] += counts[l];
+  }
+
+  let mut next = counts.clone();
+  let mut sorted: Vec<(usize, T)> = vec![(0, T::zero()); values.len()];
+  for ((m, n), val) in major.iter().zip(minor.iter()).zip(values.iter()) {
+    sorted[next[*m]] = (*n, *val);
+    next[*m] += 1;
+  }
+
+  let mut ptr: Vec<usize> = Vec::with_capacity(lanes + 1);
+  let mut idx: Vec<usize> = Vec::with_capacity(values.len());
+  let mut vals: Vec<T> = Vec::with_capacity(values.len());
+  ptr.push(0);
+  for l in 0..lanes {
+    let lane = &mut sorted[counts[l]..counts[l + 1]];
+    lane.sort_by_key(|(n, _)| *n);
+
+    let start = idx.len();
+    for (n, val) in lane.iter() {
+      if (idx.len() > start) && (idx[idx.len() - 1] == *n) {
+        let last = vals.len() - 1;
+        vals[last] = vals[last] + *val;
+      }
+      else {
+        idx.push(*n);
+        vals.push(*val);
+      }
+    }
+    ptr.push(idx.len());
+  }
+
+  (ptr, idx, vals)
+}
+
+/// Transposes compressed storage, turning CSR arrays into CSC arrays and vice versa.
+fn transpose_compressed<T: Num + Copy>(lanes: usize, others: usize, ptr: &[usize], idx: &[usize], values: &[T]) -> (Vec<usize>, Vec<usize>, Vec<T>) {
+  let mut new_ptr: Vec<usize> = vec![0; others + 1];
+  for n in idx.iter() {
+    new_ptr[n + 1] += 1;
+  }
+  for o in 0..others {
+    new_ptr[o + 1] += new_ptr[o];
+  }
+
+  let mut next = new_ptr.clone();
+  let mut new_idx: Vec<usize> = vec![0; idx.len()];
+  let mut new_values: Vec<T> = vec![T::zero(); values.len()];
+  for l in 0..lanes {
+    for k in ptr[l]..ptr[l + 1] {
+      let dest = next[idx[k]];
+      new_idx[dest] = l;
+      new_values[dest] = values[k];
+      next[idx[k]] += 1;
+    }
+  }
+
+  (new_ptr, new_idx, new_values)
+}
+
+/// Multiplies two matrices in compressed row storage, each given as `(lanes or columns, ptr, idx, values)`,
+/// accumulating each row of the product densely before compressing it again.
+fn multiply_compressed<T: Num + Copy>(a: (usize, &[usize], &[usize], &[T]), b: (usize, &[usize], &[usize], &[T])) -> (Vec<usize>, Vec<usize>, Vec<T>) {
+  let (rows, a_ptr, a_idx, a_values) = a;
+  let (cols, b_ptr, b_idx, b_values) = b;
+
+  let mut ptr: Vec<usize> = Vec::with_capacity(rows + 1);
+  let mut idx: Vec<usize> = Vec::new();
+  let mut values: Vec<T> = Vec::new();
+  let mut accumulator: Vec<T> = vec![T::zero(); cols];
+  let mut marker: Vec<usize> = vec![usize::MAX; cols];
+  ptr.push(0);
+
+  for i in 0..rows {
+    let start = idx.len();
+    for k in a_ptr[i]..a_ptr[i + 1] {
+      let (val, row) = (a_values[k], a_idx[k]);
+      for l in b_ptr[row]..b_ptr[row + 1] {
+        let j = b_idx[l];
+        if marker[j] != i {
+          marker[j] = i;
+          accumulator[j] = T::zero();
+          idx.push(j);
+        }
+        accumulator[j] = accumulator[j] + (val * b_values[l]);
+      }
+    }
+
+    idx[start..].sort_unstable();
+    for j in idx[start..].iter() {
+      values.push(accumulator[*j]);
+    }
+    ptr.push(idx.len());
+  }
+
+  (ptr, idx, values)
+}
+
+/// Returns the value at minor index `n` of a lane, using a binary search over its indices.
+fn lane_get<T: Num + Copy>(idx: &[usize], values: &[T], n: usize) -> T {
+  match idx.binary_search(&n) {
+    Ok(k) => values[k],
+    Err(_) => T::zero()
+  }
+}
+
+impl<T: Num + Copy> CooMatrix<T> {
+  /// Creates an empty `rows x columns` sparse matrix.
+  pub fn new(rows: usize, columns: usize) -> Self {
+
+    CooMatrix { rows, cols: columns, row_idx: Vec::new(), col_idx: Vec::new(), values: Vec::new() }
+  }
+
+  /// Creates an empty `rows x columns` sparse matrix with room for `capacity` values.
+  pub fn with_capacity(rows: usize, columns: usize, capacity: usize) -> Self {
+    let mut coo = Self::new(rows, columns);
+    coo.row_idx.reserve(capacity);
+    coo.col_idx.reserve(capacity);
+    coo.values.reserve(capacity);
+
+    coo
+  }
+
+  /// Adds `val` at `(i, j)`. Values at the same index are summed when the matrix is compressed.
+  pub fn push(&mut self, i: usize, j: usize, val: T) {
+    if (i >= self.rows) || (j >= self.cols) {
+      panic!("Index ({}, {}) is out of bounds for a matrix of size ({}, {})", i, j, self.rows, self.cols);
+    }
+
+    self.row_idx.push(i);
+    self.col_idx.push(j);
+    self.values.push(val);
+  }
+
+  pub fn size(&self) -> (usize, usize) {
+
+    (self.rows, self.cols)
+  }
+
+  /// Returns the number of stored values, including duplicates.
+  pub fn nnz(&self) -> usize {
+
+    self.values.len()
+  }
+
+  /// Creates an iterator over the stored `(row, column, value)` triplets.
+  pub fn iter(&self) -> impl Iterator<Item = (usize, usize, T)> + '_ {
+
+    self.row_idx.iter().zip(self.col_idx.iter()).zip(self.values.iter()).map(|((i, j), val)| (*i, *j, *val))
+  }
+
+  pub fn to_csr(&self) -> CsrMatrix<T> {
+    let (row_ptr, col_idx, values) = compress(self.rows, &self.row_idx, &self.col_idx, &self.values);
+
+    CsrMatrix { rows: self.rows, cols: self.cols, row_ptr, col_idx, values }
+  }
+
+  pub fn to_csc(&self) -> CscMatrix<T> {
+    let (col_ptr, row_idx, values) = compress(self.cols, &self.col_idx, &self.row_idx, &self.values);
+
+    CscMatrix { rows: self.rows, cols: self.cols, col_ptr, row_idx, values }
+  }
+
+  pub fn transpose(&self) -> CooMatrix<T> {
+
+    CooMatrix {
+      rows: self.cols,
+      cols: self.rows,
+      row_idx: self.col_idx.clone(),
+      col_idx: self.row_idx.clone(),
+      values: self.values.clone()
+    }
+  }
+}
+
+impl<T: Num + Copy> CsrMatrix<T> {
+  pub fn size(&self) -> (usize, usize) {
+
+    (self.rows, self.cols)
+  }
+
+  /// Returns the number of stored values.
+  pub fn nnz(&self) -> usize {
+
+    self.values.len()
+  }
+
+  /// Returns the value at `(i, j)`, which is zero if it is not stored.
+  pub fn get(&self, i: usize, j: usize) -> T {
+    if (i >= self.rows) || (j >= self.cols) {
+      panic!("Index ({}, {}) is out of bounds for a matrix of size ({}, {})", i, j, self.rows, self.cols);
+    }
+    let lane = self.row_ptr[i]..self.row_ptr[i + 1];
+
+    lane_get(&self.col_idx[lane.clone()], &self.values[lane], j)
+  }
+
+  /// Returns the column indices and values stored in row `i`.
+  pub fn row(&self, i: usize) -> (&[usize], &[T]) {
+    let lane = self.row_ptr[i]..self.row_ptr[i + 1];
+
+    (&self.col_idx[lane.clone()], &self.values[lane])
+  }
+
+  pub fn row_ptr(&self) -> &[usize] {
+
+    &self.row_ptr
+  }
+
+  pub fn col_idx(&self) -> &[usize] {
+
+    &self.col_idx
+  }
+
+  pub fn values(&self) -> &[T] {
+
+    &self.values
+  }
+
+  /// Creates an iterator over the stored `(row, column, value)` triplets in row order.
+  pub fn iter(&self) -> impl Iterator<Item = (usize, usize, T)> + '_ {
+
+    (0..self.rows).flat_map(move |i| {
+      (self.row_ptr[i]..self.row_ptr[i + 1]).map(move |k| (i, self.col_idx[k], self.values[k]))
+    })
+  }
+
+  pub fn to_coo(&self) -> CooMatrix<T> {
+    let mut coo = CooMatrix::with_capacity(self.rows, self.cols, self.nnz());
+    for (i, j, val) in self.iter() {
+      coo.push(i, j, val);
+    }
+
+    coo
+  }
+
+  pub fn to_csc(&self) -> CscMatrix<T> {
+    let (col_ptr, row_idx, values) = transpose_compressed(self.rows, self.cols, &self.row_ptr, &self.col_idx, &self.values);
+
+    CscMatrix { rows: self.rows, cols: self.cols, col_ptr, row_idx, values }
+  }
+
+  pub fn transpose(&self) -> CsrMatrix<T> {
+    let (row_ptr, col_idx, values) = transpose_compressed(self.rows, self.cols, &self.row_ptr, &self.col_idx, &self.values);
+
+    CsrMatrix { rows: self.cols, cols: self.rows, row_ptr, col_idx, values }
+  }
+
+  pub fn t(&self) -> CsrMatrix<T> {
+
+    self.transpose()
+  }
+
+  /// Multiplies the matrix by the vector `x`.
+  pub fn mul_vec(&self, x: &[T]) -> Result<Vec<T>, String> {
+    if self.cols != x.len() {
+      let s = format!("Cannot multiply a matrix of size ({}, {}) and a vector of length {} together",
+                              self.rows, self.cols, x.len());
+      return Err(s);
+    }
+
+    let mut y: Vec<T> = Vec::with_capacity(self.rows);
+    for i in 0..self.rows {
+      let mut sum = T::zero();
+      for k in self.row_ptr[i]..self.row_ptr[i + 1] {
+        sum = sum + (self.values[k] * x[self.col_idx[k]]);
+      }
+      y.push(sum);
+    }
+
+    Ok(y)
+  }
+
+  /// Multiplies two sparse matrices, keeping the product sparse.
+  pub fn mul_sparse(&self, other: &CsrMatrix<T>) -> Result<CsrMatrix<T>, String> {
+    if self.cols != other.rows {
+      let s = format!("Cannot multiply matrices of size ({}, {}) and size ({}, {}) together",
+                              self.rows, self.cols, other.rows, other.cols);
+      return Err(s);
+    }
+
+    let (row_ptr, col_idx, values) = multiply_compressed(
+      (self.rows, &self.row_ptr, &self.col_idx, &self.values),
+      (other.cols, &other.row_ptr, &other.col_idx, &other.values)
+    );
+
+    Ok(CsrMatrix { rows: self.rows, cols: other.cols, row_ptr, col_idx, values })
+  }
+}
+
+impl<T: Float + fmt::Debug> CsrMatrix<T> {
+  /// Multiplies the matrix by a dense matrix, giving a dense product.
+  pub fn mul_dense(&self, other: &FloatMatrix<T>) -> Result<FloatMatrix<T>, String> {
+    let (rows, cols) = other.size();
+    if self.cols != rows {
+      let s = format!("Cannot multiply matrices of size ({}, {}) and size ({}, {}) together",
+                              self.rows, self.cols, rows, cols);
+      return Err(s);
+    }
+
+    let b: Vec<T> = other.iter().collect();
+    let mut product: Vec<T> = vec![T::zero(); self.rows * cols];
+    for i in 0..self.rows {
+      let out = &mut product[(i * cols)..((i + 1) * cols)];
+      for k in self.row_ptr[i]..self.row_ptr[i + 1] {
+        let (a, row) = (self.values[k], self.col_idx[k]);
+        for (val, b) in out.iter_mut().zip(b[(row * cols)..((row + 1) * cols)].iter()) {
+          *val = *val + (a * *b);
+        }
+      }
+    }
+
+    Ok(FloatMatrix::from_1d_vec(product, self.rows, cols))
+  }
+
+  pub fn to_dense(&self) -> FloatMatrix<T> {
+    let mut dense: FloatMatrix<T> = FloatMatrix::new(self.rows, self.cols);
+    for (i, j, val) in self.iter() {
+      dense[(i, j)] = val;
+    }
+
+    dense
+  }
+}
+
+impl<T: Num + Copy> CscMatrix<T> {
+  pub fn size(&self) -> (usize, usize) {
+
+    (self.rows, self.cols)
+  }
+
+  /// Returns the number of stored values.
+  pub fn nnz(&self) -> usize {
+
+    self.values.len()
+  }
+
+  /// Returns the value at `(i, j)`, which is zero if it is not stored.
+  pub fn get(&self, i: usize, j: usize) -> T {
+    if (i >= self.rows) || (j >= self.cols) {
+      panic!("Index ({}, {}) is out of bounds for a matrix of size ({}, {})", i, j, self.rows, self.cols);
+    }
+    let lane = self.col_ptr[j]..self.col_ptr[j + 1];
+
+    lane_get(&self.row_idx[lane.clone()], &self.values[lane], i)
+  }
+
+  /// Returns the row indices and values stored in column `j`.
+  pub fn col(&self, j: usize) -> (&[usize], &[T]) {
+    let lane = self.col_ptr[j]..self.col_ptr[j + 1];
+
+    (&self.row_idx[lane.clone()], &self.values[lane])
+  }
+
+  pub fn col_ptr(&self) -> &[usize] {
+
+    &self.col_ptr
+  }
+
+  pub fn row_idx(&self) -> &[usize] {
+
+    &self.row_idx
+  }
+
+  pub fn values(&self) -> &[T] {
+
+    &self.values
+  }
+
+  /// Creates an iterator over the stored `(row, column, value)` triplets in column order.
+  pub fn iter(&self) -> impl Iterator<Item = (usize, usize, T)> + '_ {
+
+    (0..self.cols).flat_map(move |j| {
+      (self.col_ptr[j]..self.col_ptr[j + 1]).map(move |k| (self.row_idx[k], j, self.values[k]))
+    })
+  }
+
+  pub fn to_coo(&self) -> CooMatrix<T> {
+    let mut coo = CooMatrix::with_capacity(self.rows, self.cols, self.nnz());
+    for (i, j, val) in self.iter() {
+      coo.push(i, j, val);
+    }
+
+    coo
+  }
+
+  pub fn to_csr(&self) -> CsrMatrix<T> {
+    let (row_ptr, col_idx, values) = transpose_compressed(self.cols, self.rows, &self.col_ptr, &self.row_idx, &self.values);
+
+    CsrMatrix { rows: self.rows, cols: self.cols, row_ptr, col_idx, values }
+  }
+
+  pub fn transpose(&self) -> CscMatrix<T> {
+    let (col_ptr, row_idx, values) = transpose_compressed(self.cols, self.rows, &self.col_ptr, &self.row_idx, &self.values);
+
+    CscMatrix { rows: self.cols, cols: self.rows, col_ptr, row_idx, values }
+  }
+
+  pub fn t(&self) -> CscMatrix<T> {
+
+    self.transpose()
+  }
+
+  /// Multiplies the matrix by the vector `x`.
+  pub fn mul_vec(&self, x: &[T]) -> Result<Vec<T>, String> {
+    if self.cols != x.len() {
+      let s = format!("Cannot multiply a matrix of size ({}, {}) and a vector of length {} together",
+                              self.rows, self.cols, x.len());
+      return Err(s);
+    }
+
+    let mut y: Vec<T> = vec![T::zero(); self.rows];
+    for (j, x) in x.iter().enumerate() {
+      for k in self.col_ptr[j]..self.col_ptr[j + 1] {
+        y[self.row_idx[k]] = y[self.row_idx[k]] + (self.values[k] * *x);
+      }
+    }
+
+    Ok(y)
+  }
+
+  /// Multiplies two sparse matrices, keeping the product sparse.
+  pub fn mul_sparse(&self, other: &CscMatrix<T>) -> Result<CscMatrix<T>, String> {
+    if self.cols != other.rows {
+      let s = format!("Cannot multiply matrices of size ({}, {}) and size ({}, {}) together",
+                              self.rows, self.cols, other.rows, other.cols);
+      return Err(s);
+    }
+
+    // The CSC arrays of a matrix are the CSR arrays of its transpose, and (AB)^T = B^T A^T.
+    let (col_ptr, row_idx, values) = multiply_compressed(
+      (other.cols, &other.col_ptr, &other.row_idx, &other.values),
+      (self.rows, &self.col_ptr, &self.row_idx, &self.values)
+    );
+
+    Ok(CscMatrix { rows: self.rows, cols: other.cols, col_ptr, row_idx, values })
+  }
+}
+
+impl<T: Float + fmt::Debug> CscMatrix<T> {
+  /// Multiplies the matrix by a dense matrix, giving a dense product.
+  pub fn mul_dense(&self, other: &FloatMatrix<T>) -> Result<FloatMatrix<T>, String> {
+    let (rows, cols) = other.size();
+    if self.cols != rows {
+      let s = format!("Cannot multiply matrices of size ({}, {}) and size ({}, {}) together",
+                              self.rows, self.cols, rows, cols);
+      return Err(s);
+    }
+
+    let b: Vec<T> = other.iter().collect();
+    let mut product: Vec<T> = vec![T::zero(); self.rows * cols];
+    for j in 0..self.cols {
+      let b_row = &b[(j * cols)..((j + 1) * cols)];
+      for k in self.col_ptr[j]..self.col_ptr[j + 1] {
+        let (a, i) = (self.values[k], self.row_idx[k]);
+        for (val, b) in product[(i * cols)..((i + 1) * cols)].iter_mut().zip(b_row.iter()) {
+          *val = *val + (a * *b);
+        }
+      }
+    }
+
+    Ok(FloatMatrix::from_1d_vec(product, self.rows, cols))
+  }
+
+  pub fn to_dense(&self) -> FloatMatrix<T> {
+    let mut dense: FloatMatrix<T> = FloatMatrix::new(self.rows, self.cols);
+    for (i, j, val) in self.iter() {
+      dense[(i, j)] = val;
+    }
+
+    dense
+  }
+}
+
+impl<T: Float + fmt::Debug> From<&FloatMatrix<T>> for CooMatrix<T> {
+  fn from(m: &FloatMatrix<T>) -> Self {
+    let (rows, cols) = m.size();
+    let mut coo = CooMatrix::new(rows, cols);
+    for ((i, j), val) in m.indexed_iter() {
+      if val != T::zero() {
+        coo.push(i, j, val);
+      }
+    }
+
+    coo
+  }
+}
+
+impl<T: Float + fmt::Debug> From<&FloatMatrix<T>> for CsrMatrix<T> {
+  fn from(m: &FloatMatrix<T>) -> Self {
+
+    CooMatrix::from(m).to_csr()
+  }
+}
+
+impl<T: Float + fmt::Debug> From<&FloatMatrix<T>> for CscMatrix<T> {
+  fn from(m: &FloatMatrix<T>) -> Self {
+
+    CooMatrix::from(m).to_csc()
+  }
+}
+
+impl<T: Float + fmt::Debug> From<CsrMatrix<T>> for FloatMatrix<T> {
+  fn from(m: CsrMatrix<T>) -> Self {
+
+    m.to_dense()
+  }
+}
+
+impl<T: Float + fmt::Debug> From<CscMatrix<T>> for FloatMatrix<T> {
+  fn from(m: CscMatrix<T>) -> Self {
+
+    m.to_dense()
+  }
+}
+
+impl<T: Num + Copy> Mul for CsrMatrix<T> {
+  type Output = Result<Self, String>;
+
+  fn mul(self, other: Self) -> Result<Self, String> {
+
+    self.mul_sparse(&other)
+  }
+}
+
+impl<T: Float + fmt::Debug> Mul<FloatMatrix<T>> for CsrMatrix<T> {
+  type Output = Result<FloatMatrix<T>, String>;
+
+  fn mul(self, other: FloatMatrix<T>) -> Result<FloatMatrix<T>, String> {
+
+    self.mul_dense(&other)
+  }
+}
+
+impl<T: Float + fmt::Debug> Mul<CsrMatrix<T>> for FloatMatrix<T> {
+  type Output = Result<FloatMatrix<T>, String>;
+
+  fn mul(self, other: CsrMatrix<T>) -> Result<FloatMatrix<T>, String> {
+    // A * S = (S^T * A^T)^T
+    let product = other.transpose().mul_dense(&self.into_transpose())?;
+
+    Ok(product.into_transpose())
+  }
+}
+
+impl<T: Num + Copy> Mul for CscMatrix<T> {
+  type Output = Result<Self, String>;
+
+  fn mul(self, other: Self) -> Result<Self, String> {
+
+    self.mul_sparse(&other)
+  }
+}
+
+impl<T: Float + fmt::Debug> Mul<FloatMatrix<T>> for CscMatrix<T> {
+  type Output = Result<FloatMatrix<T>, String>;
+
+  fn mul(self, other: FloatMatrix<T>) -> Result<FloatMatrix<T>, String> {
+
+    self.mul_dense(&other)
+  }
+}
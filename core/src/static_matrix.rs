@@ -0,0 +1,247 @@
+use num::{
+  Float,
+  Integer,
+  Num,
+  NumCast
+};
+
+use std::{
+  fmt,
+  ops::{
+    Add,
+    Sub,
+    Mul,
+    Index,
+    IndexMut
+  }
+};
+
+use crate::{Matrix, FloatMatrix};
+
+/// A 2-dimensional matrix whose size `(R, C)` is fixed at compile time.
+///
+/// The elements are stored inline as `[[T; C]; R]`, so an `SMatrix` never allocates and
+/// mismatched shapes in `+`, `-` and `*` are rejected by the compiler instead of at runtime.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SMatrix<T: Num + Copy, const R: usize, const C: usize> {
+  matrix: [[T; C]; R]
+}
+
+impl<T: Num + Copy, const R: usize, const C: usize> SMatrix<T, R, C> {
+  /// Creates a new `R x C` matrix with all values initialized to zero.
+  pub fn new() -> Self {
+
+    SMatrix { matrix: [[T::zero(); C]; R] }
+  }
+
+  /// Creates a matrix from an array of rows.
+  pub fn from_array(matrix: [[T; C]; R]) -> Self {
+
+    SMatrix { matrix }
+  }
+
+  /// Consumes the matrix and returns its array of rows.
+  pub fn into_array(self) -> [[T; C]; R] {
+
+    self.matrix
+  }
+
+  /// Creates an iterator over the values of the matrix in row-major order.
+  pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+
+    self.matrix.iter().flat_map(|row| row.iter().copied())
+  }
+
+  pub fn size(&self) -> (usize, usize) {
+
+    (R, C)
+  }
+
+  pub fn transpose(&self) -> SMatrix<T, C, R> {
+    let mut trans = [[T::zero(); R]; C];
+    for (i, row) in self.matrix.iter().enumerate() {
+      for (j, val) in row.iter().enumerate() {
+        trans[j][i] = *val;
+      }
+    }
+
+    SMatrix { matrix: trans }
+  }
+
+  pub fn t(&self) -> SMatrix<T, C, R> {
+
+    self.transpose()
+  }
+}
+
+impl<T: Num + Copy, const N: usize> SMatrix<T, N, N> {
+  pub fn identity() -> Self {
+    let mut identity = Self::new();
+
+    for i in 0..N {
+      identity.matrix[i][i] = T::one();
+    }
+
+    identity
+  }
+}
+
+impl<T: Num + Copy, const R: usize, const C: usize> Default for SMatrix<T, R, C> {
+  fn default() -> Self {
+
+    Self::new()
+  }
+}
+
+impl<T: Num + Copy, const R: usize, const C: usize> Add for SMatrix<T, R, C> {
+  type Output = Self;
+
+  fn add(self, other: Self) -> Self {
+    let mut sum = self;
+    for i in 0..R {
+      for j in 0..C {
+        sum.matrix[i][j] = self.matrix[i][j] + other.matrix[i][j];
+      }
+    }
+
+    sum
+  }
+}
+
+impl<T: Num + Copy, const R: usize, const C: usize> Sub for SMatrix<T, R, C> {
+  type Output = Self;
+
+  fn sub(self, other: Self) -> Self {
+    let mut diff = self;
+    for i in 0..R {
+      for j in 0..C {
+        diff.matrix[i][j] = self.matrix[i][j] - other.matrix[i][j];
+      }
+    }
+
+    diff
+  }
+}
+
+impl<T: Num + Copy, const R: usize, const K: usize, const C: usize> Mul<SMatrix<T, K, C>> for SMatrix<T, R, K> {
+  type Output = SMatrix<T, R, C>;
+
+  fn mul(self, other: SMatrix<T, K, C>) -> SMatrix<T, R, C> {
+    let mut product = [[T::zero(); C]; R];
+    for (row, a_row) in product.iter_mut().zip(self.matrix.iter()) {
+      for (a, b_row) in a_row.iter().zip(other.matrix.iter()) {
+        for (val, b) in row.iter_mut().zip(b_row.iter()) {
+          *val = *val + *a * *b;
+        }
+      }
+    }
+
+    SMatrix { matrix: product }
+  }
+}
+
+impl<T: Num + Copy + fmt::Display, const R: usize, const C: usize> fmt::Display for SMatrix<T, R, C> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let mut text: String = String::from("");
+    text.push_str(&format!("Matrix at addr {:p}:\n", self));
+
+    for row in self.matrix.iter() {
+      text.push_str("[ ");
+      for val in row.iter() {
+        let s = format!("{:>4} ", val);
+        text.push_str(&s);
+      }
+      text.push_str("]\n");
+    }
+
+    write!(f, "{}", text)
+  }
+}
+
+impl<T: Num + Copy, const R: usize, const C: usize> Index<(usize, usize)> for SMatrix<T, R, C> {
+  type Output = T;
+
+  fn index(&self, index: (usize, usize)) -> &Self::Output {
+    let (i, j) = index;
+
+    &self.matrix[i][j]
+  }
+}
+
+impl<T: Num + Copy, const R: usize, const C: usize> IndexMut<(usize, usize)> for SMatrix<T, R, C> {
+  fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+    let (i, j) = index;
+
+    &mut self.matrix[i][j]
+  }
+}
+
+impl<T: Float + fmt::Debug, const R: usize, const C: usize> From<SMatrix<T, R, C>> for FloatMatrix<T> {
+  fn from(m: SMatrix<T, R, C>) -> Self {
+    let mut mat: FloatMatrix<T> = FloatMatrix::new(R, C);
+    for i in 0..R {
+      for j in 0..C {
+        mat[(i, j)] = m.matrix[i][j];
+      }
+    }
+
+    mat
+  }
+}
+
+impl<T: Float + fmt::Debug, const R: usize, const C: usize> TryFrom<FloatMatrix<T>> for SMatrix<T, R, C> {
+  type Error = String;
+
+  fn try_from(m: FloatMatrix<T>) -> Result<Self, String> {
+    if m.size() != (R, C) {
+      let (rows, cols) = m.size();
+      let s = format!("Cannot convert a matrix of size ({}, {}) into a static matrix of size ({}, {})",
+                              rows, cols, R, C);
+      return Err(s);
+    }
+
+    let mut mat = Self::new();
+    for i in 0..R {
+      for j in 0..C {
+        mat.matrix[i][j] = m[(i, j)];
+      }
+    }
+
+    Ok(mat)
+  }
+}
+
+impl<T: Integer + NumCast + Clone + Copy, const R: usize, const C: usize> From<SMatrix<T, R, C>> for Matrix<T> {
+  fn from(m: SMatrix<T, R, C>) -> Self {
+    let mut mat: Matrix<T> = Matrix::new(R, C);
+    for i in 0..R {
+      for j in 0..C {
+        mat[(i, j)] = m.matrix[i][j];
+      }
+    }
+
+    mat
+  }
+}
+
+impl<T: Integer + NumCast + Clone + Copy, const R: usize, const C: usize> TryFrom<Matrix<T>> for SMatrix<T, R, C> {
+  type Error = String;
+
+  fn try_from(m: Matrix<T>) -> Result<Self, String> {
+    if m.size() != (R, C) {
+      let (rows, cols) = m.size();
+      let s = format!("Cannot convert a matrix of size ({}, {}) into a static matrix of size ({}, {})",
+                              rows, cols, R, C);
+      return Err(s);
+    }
+
+    let mut mat = Self::new();
+    for i in 0..R {
+      for j in 0..C {
+        mat.matrix[i][j] = m[(i, j)];
+      }
+    }
+
+    Ok(mat)
+  }
+}
@@ -0,0 +1,125 @@
+use num::Num;
+
+use crate::kernel::{Operand, gemm};
+use crate::layout::Layout;
+use crate::parallel::MaybeSync;
+use crate::simd;
+
+/// The size in any dimension at or below which products use the blocked kernel directly.
+const CROSSOVER: usize = 256;
+
+/// Copies the row-major `rows x cols` matrix `x` into the top-left corner of a zero
+/// `new_rows x new_cols` matrix, dropping the values that do not fit.
+fn resize<T: Num + Copy>(x: &[T], rows: usize, cols: usize, new_rows: usize, new_cols: usize) -> Vec<T> {
+  let mut out: Vec<T> = vec![T::zero(); new_rows * new_cols];
+  let width = cols.min(new_cols);
+  for i in 0..rows.min(new_rows) {
+    out[(i * new_cols)..((i * new_cols) + width)].copy_from_slice(&x[(i * cols)..((i * cols) + width)]);
+  }
+
+  out
+}
+
+/// Splits the row-major `rows x cols` matrix `x`, with both dimensions even, into its four
+/// quadrants in row-major order.
+fn quadrants<T: Num + Copy>(x: &[T], rows: usize, cols: usize) -> [Vec<T>; 4] {
+  let (h, w) = (rows / 2, cols / 2);
+  let quadrant = |qi: usize, qj: usize| -> Vec<T> {
+    (0..h).flat_map(|i| x[((((qi * h) + i) * cols) + (qj * w))..((((qi * h) + i) * cols) + ((qj + 1) * w))].iter().copied())
+          .collect()
+  };
+
+  [quadrant(0, 0), quadrant(0, 1), quadrant(1, 0), quadrant(1, 1)]
+}
+
+/// Joins four `rows x cols` quadrants, in row-major order, into one matrix.
+fn join<T: Num + Copy>(quadrants: [Vec<T>; 4], rows: usize, cols: usize) -> Vec<T> {
+  let mut out: Vec<T> = Vec::with_capacity(4 * rows * cols);
+  for (left, right) in [(0, 1), (2, 3)] {
+    for i in 0..rows {
+      out.extend_from_slice(&quadrants[left][(i * cols)..((i + 1) * cols)]);
+      out.extend_from_slice(&quadrants[right][(i * cols)..((i + 1) * cols)]);
+    }
+  }
+
+  out
+}
+
+fn add<T: Num + Copy + 'static>(x: &[T], y: &[T]) -> Vec<T> {
+  let mut out: Vec<T> = vec![T::zero(); x.len()];
+  simd::add(x, y, &mut out);
+
+  out
+}
+
+fn sub<T: Num + Copy + 'static>(x: &[T], y: &[T]) -> Vec<T> {
+  let mut out: Vec<T> = vec![T::zero(); x.len()];
+  simd::sub(x, y, &mut out);
+
+  out
+}
+
+/// Multiplies the row-major `m x k` matrix `a` by the row-major `k x n` matrix `b` with
+/// Winograd's variant of Strassen's algorithm, which needs 7 half-size products and 15 additions
+/// per level instead of 8 products.
+///
+/// The recursion halves every dimension until the smallest is at most [CROSSOVER], so the
+/// matrices are padded with zeros once, up front, to dimensions that divide evenly that often.
+pub(crate) fn strassen<T: Num + Copy + MaybeSync + 'static>(a: &[T], b: &[T], m: usize, k: usize, n: usize) -> Vec<T> {
+  let mut levels: u32 = 0;
+  let mut size = m.min(k).min(n);
+  while size > CROSSOVER {
+    size = size.div_ceil(2);
+    levels += 1;
+  }
+  let step = 1 << levels;
+  let (pm, pk, pn) = (m.next_multiple_of(step), k.next_multiple_of(step), n.next_multiple_of(step));
+  if (pm, pk, pn) == (m, k, n) {
+    return winograd(a, b, m, k, n, levels);
+  }
+  let c = winograd(&resize(a, m, k, pm, pk), &resize(b, k, n, pk, pn), pm, pk, pn, levels);
+
+  resize(&c, pm, pn, m, n)
+}
+
+/// Computes the product of [strassen] with `levels` levels of recursion, for dimensions that are
+/// multiples of `2^levels`.
+fn winograd<T: Num + Copy + MaybeSync + 'static>(a: &[T], b: &[T], m: usize, k: usize, n: usize, levels: u32) -> Vec<T> {
+  if levels == 0 {
+    let mut c: Vec<T> = vec![T::zero(); m * n];
+    gemm(&Operand::new(a, m, k, Layout::RowMajor), &Operand::new(b, k, n, Layout::RowMajor), &mut c);
+
+    return c;
+  }
+
+  let (m2, k2, n2, next) = (m / 2, k / 2, n / 2, levels - 1);
+  let [a11, a12, a21, a22] = quadrants(a, m, k);
+  let [b11, b12, b21, b22] = quadrants(b, k, n);
+
+  let s1 = add(&a21, &a22);
+  let s2 = sub(&s1, &a11);
+  let s3 = sub(&a11, &a21);
+  let s4 = sub(&a12, &s2);
+  let t1 = sub(&b12, &b11);
+  let t2 = sub(&b22, &t1);
+  let t3 = sub(&b22, &b12);
+  let t4 = sub(&t2, &b21);
+
+  let p1 = winograd(&a11, &b11, m2, k2, n2, next);
+  let p2 = winograd(&a12, &b21, m2, k2, n2, next);
+  let p3 = winograd(&s4, &b22, m2, k2, n2, next);
+  let p4 = winograd(&a22, &t4, m2, k2, n2, next);
+  let p5 = winograd(&s1, &t1, m2, k2, n2, next);
+  let p6 = winograd(&s2, &t2, m2, k2, n2, next);
+  let p7 = winograd(&s3, &t3, m2, k2, n2, next);
+
+  let u2 = add(&p1, &p6);
+  let u3 = add(&u2, &p7);
+  let u4 = add(&u2, &p5);
+  let c11 = add(&p1, &p2);
+  let c12 = add(&u4, &p3);
+  let c21 = sub(&u3, &p4);
+  let c22 = add(&u3, &p5);
+
+  join([c11, c12, c21, c22], m2, n2)
+}
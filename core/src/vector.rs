@@ -0,0 +1,364 @@
+use num::Float;
+
+use std::{
+  fmt,
+  ops::{
+    Add,
+    Sub,
+    Mul,
+    Index,
+    IndexMut
+  }
+};
+
+use crate::FloatMatrix;
+use crate::simd;
+
+/// A column vector of floating-point numbers, equivalent to an `n x 1` [FloatMatrix].
+pub struct Vector<T: Float> {
+  vector: Vec<T>
+}
+
+/// A row vector of floating-point numbers, equivalent to a `1 x n` [FloatMatrix].
+pub struct RowVector<T: Float> {
+  vector: Vec<T>
+}
+
+macro_rules! impl_vector {
+  ($($name:ident, $orientation:literal;)*) => {
+    $(
+      impl<T: Float> $name<T> {
+        #[doc = concat!("Creates a new ", $orientation, " vector of length `len` with all values initialized to zero.")]
+        pub fn new(len: usize) -> Self {
+          if len < 1 {
+            panic!("Cannot initialize a vector with length less than 1");
+          }
+
+          $name { vector: vec![T::zero(); len] }
+        }
+
+        #[doc = concat!("Creates a ", $orientation, " vector from the values of `vector`.")]
+        pub fn from_vec(vector: Vec<T>) -> Self {
+          if vector.is_empty() {
+            panic!("Cannot initialize a vector with length less than 1");
+          }
+
+          $name { vector }
+        }
+
+        pub fn len(&self) -> usize {
+
+          self.vector.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+
+          self.vector.is_empty()
+        }
+
+        pub fn as_slice(&self) -> &[T] {
+
+          &self.vector
+        }
+
+        /// Creates an iterator for the current vector.
+        pub fn iter(&self) -> std::iter::Copied<std::slice::Iter<'_, T>> {
+
+          self.vector.iter().copied()
+        }
+
+        /// Returns the dot product of two vectors of the same length.
+        ///
+        /// The products are added into eight interleaved partial sums, which lets `f32` and `f64`
+        /// use SIMD instructions, so the result can differ in the last bits from a sequential sum.
+        pub fn dot(&self, other: &Self) -> Result<T, String>
+          where T: 'static {
+          if self.len() != other.len() {
+            let s = format!("Cannot take the dot product of vectors of length {} and length {}",
+                                    self.len(), other.len());
+            return Err(s);
+          }
+
+          Ok(simd::dot(&self.vector, &other.vector))
+        }
+
+        /// Returns the Euclidean norm of the vector.
+        pub fn norm(&self) -> T
+          where T: 'static {
+
+          simd::dot(&self.vector, &self.vector).sqrt()
+        }
+
+        /// Returns a vector with the same direction and a norm of one.
+        pub fn normalize(&self) -> Result<Self, String>
+          where T: 'static {
+          let norm = self.norm();
+          if norm == T::zero() {
+            return Err(String::from("Cannot normalize a vector with a norm of zero"));
+          }
+
+          Ok($name { vector: self.vector.iter().map(|val| *val / norm).collect() })
+        }
+      }
+
+      impl<T: Float + 'static> Add for $name<T> {
+        type Output = Result<Self, String>;
+
+        fn add(self, other: Self) -> Result<Self, String> {
+          if self.len() != other.len() {
+            let s = format!("Cannot add vectors of length {} and length {} together",
+                                    self.len(), other.len());
+            return Err(s);
+          }
+
+          let mut vector: Vec<T> = vec![T::zero(); self.len()];
+          simd::add(&self.vector, &other.vector, &mut vector);
+
+          Ok($name { vector })
+        }
+      }
+
+      impl<T: Float + 'static> Sub for $name<T> {
+        type Output = Result<Self, String>;
+
+        fn sub(self, other: Self) -> Result<Self, String> {
+          if self.len() != other.len() {
+            let s = format!("Cannot subtract vectors of length {} and length {}",
+                                    self.len(), other.len());
+            return Err(s);
+          }
+
+          let mut vector: Vec<T> = vec![T::zero(); self.len()];
+          simd::sub(&self.vector, &other.vector, &mut vector);
+
+          Ok($name { vector })
+        }
+      }
+
+      impl<T: Float + fmt::Display> fmt::Display for $name<T> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+          let mut text: String = String::from("");
+          text.push_str(&format!("Vector at addr {:p}:\n", self));
+
+          text.push_str("[ ");
+          for val in self.vector.iter() {
+            let s = format!("{:>4.4} ", val);
+            text.push_str(&s);
+          }
+          text.push_str("]\n");
+
+          write!(f, "{}", text)
+        }
+      }
+
+      impl<T: Float> IntoIterator for $name<T> {
+        type Item = T;
+        type IntoIter = std::vec::IntoIter<Self::Item>;
+
+        fn into_iter(self) -> Self::IntoIter {
+
+          self.vector.into_iter()
+        }
+      }
+
+      impl<T: Float> Index<usize> for $name<T> {
+        type Output = T;
+
+        fn index(&self, index: usize) -> &Self::Output {
+
+          &self.vector[index]
+        }
+      }
+
+      impl<T: Float> IndexMut<usize> for $name<T> {
+        fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+
+          &mut self.vector[index]
+        }
+      }
+
+      impl<T: Float> Clone for $name<T> {
+        fn clone(&self) -> Self {
+
+          $name { vector: self.vector.clone() }
+        }
+      }
+    )*
+  }
+}
+
+impl_vector! {
+  Vector, "column";
+  RowVector, "row";
+}
+
+impl<T: Float> Vector<T> {
+  pub fn transpose(&self) -> RowVector<T> {
+
+    RowVector { vector: self.vector.clone() }
+  }
+
+  pub fn t(&self) -> RowVector<T> {
+
+    self.transpose()
+  }
+
+  /// Returns the cross product of two vectors of length 3.
+  pub fn cross(&self, other: &Self) -> Result<Self, String> {
+    if (self.len() != 3) || (other.len() != 3) {
+      let s = format!("Cannot take the cross product of vectors of length {} and length {}",
+                              self.len(), other.len());
+      return Err(s);
+    }
+
+    let (a, b) = (&self.vector, &other.vector);
+    let vector = vec![
+      (a[1] * b[2]) - (a[2] * b[1]),
+      (a[2] * b[0]) - (a[0] * b[2]),
+      (a[0] * b[1]) - (a[1] * b[0]),
+    ];
+
+    Ok(Vector { vector })
+  }
+}
+
+impl<T: Float + fmt::Debug> Vector<T> {
+  /// Returns the outer product `self * other^T` as a `self.len() x other.len()` matrix.
+  pub fn outer(&self, other: &Self) -> FloatMatrix<T> {
+    let mut outer: FloatMatrix<T> = FloatMatrix::new(self.len(), other.len());
+    for (i, a) in self.vector.iter().enumerate() {
+      for (j, b) in other.vector.iter().enumerate() {
+        outer[(i, j)] = *a * *b;
+      }
+    }
+
+    outer
+  }
+}
+
+impl<T: Float> RowVector<T> {
+  pub fn transpose(&self) -> Vector<T> {
+
+    Vector { vector: self.vector.clone() }
+  }
+
+  pub fn t(&self) -> Vector<T> {
+
+    self.transpose()
+  }
+}
+
+impl<T: Float + fmt::Debug + 'static> Mul<Vector<T>> for FloatMatrix<T> {
+  type Output = Result<Vector<T>, String>;
+
+  fn mul(self, other: Vector<T>) -> Result<Vector<T>, String> {
+    let (rows, cols) = self.size();
+    if cols != other.len() {
+      let s = format!("Cannot multiply a matrix of size ({}, {}) and a vector of length {} together",
+                              rows, cols, other.len());
+      return Err(s);
+    }
+
+    let mut vector: Vec<T> = Vec::with_capacity(rows);
+    let mut row: Vec<T> = Vec::with_capacity(cols);
+    for i in 0..rows {
+      if let Some(slice) = self.row_slice(i) {
+        vector.push(simd::dot(slice, &other.vector));
+        continue;
+      }
+      row.clear();
+      row.extend((0..cols).map(|j| self[(i, j)]));
+      vector.push(simd::dot(&row, &other.vector));
+    }
+
+    Ok(Vector { vector })
+  }
+}
+
+impl<T: Float + fmt::Debug + 'static> Mul<FloatMatrix<T>> for RowVector<T> {
+  type Output = Result<RowVector<T>, String>;
+
+  fn mul(self, other: FloatMatrix<T>) -> Result<RowVector<T>, String> {
+    let (rows, cols) = other.size();
+    if self.len() != rows {
+      let s = format!("Cannot multiply a vector of length {} and a matrix of size ({}, {}) together",
+                              self.len(), rows, cols);
+      return Err(s);
+    }
+
+    let mut vector: Vec<T> = vec![T::zero(); cols];
+    for (i, a) in self.vector.iter().enumerate() {
+      if let Some(slice) = other.row_slice(i) {
+        simd::axpy(*a, slice, &mut vector);
+        continue;
+      }
+      for (j, val) in vector.iter_mut().enumerate() {
+        *val = *val + (*a * other[(i, j)]);
+      }
+    }
+
+    Ok(RowVector { vector })
+  }
+}
+
+impl<T: Float + 'static> Mul<Vector<T>> for RowVector<T> {
+  type Output = Result<T, String>;
+
+  fn mul(self, other: Vector<T>) -> Result<T, String> {
+
+    self.dot(&other.transpose())
+  }
+}
+
+impl<T: Float + fmt::Debug> Mul<RowVector<T>> for Vector<T> {
+  type Output = FloatMatrix<T>;
+
+  fn mul(self, other: RowVector<T>) -> FloatMatrix<T> {
+
+    self.outer(&other.transpose())
+  }
+}
+
+impl<T: Float + fmt::Debug> From<Vector<T>> for FloatMatrix<T> {
+  fn from(v: Vector<T>) -> Self {
+    let rows = v.len();
+
+    FloatMatrix::from_1d_vec(v.vector, rows, 1)
+  }
+}
+
+impl<T: Float + fmt::Debug> From<RowVector<T>> for FloatMatrix<T> {
+  fn from(v: RowVector<T>) -> Self {
+    let cols = v.len();
+
+    FloatMatrix::from_1d_vec(v.vector, 1, cols)
+  }
+}
+
+impl<T: Float + fmt::Debug> TryFrom<FloatMatrix<T>> for Vector<T> {
+  type Error = String;
+
+  fn try_from(m: FloatMatrix<T>) -> Result<Self, String> {
+    let (rows, cols) = m.size();
+    if cols != 1 {
+      let s = format!("Cannot convert a matrix of size ({}, {}) into a column vector", rows, cols);
+      return Err(s);
+    }
+
+    Ok(Vector { vector: m.into_iter().collect() })
+  }
+}
+
+impl<T: Float + fmt::Debug> TryFrom<FloatMatrix<T>> for RowVector<T> {
+  type Error = String;
+
+  fn try_from(m: FloatMatrix<T>) -> Result<Self, String> {
+    let (rows, cols) = m.size();
+    if rows != 1 {
+      let s = format!("Cannot convert a matrix of size ({}, {}) into a row vector", rows, cols);
+      return Err(s);
+    }
+
+    Ok(RowVector { vector: m.into_iter().collect() })
+  }
+}
@@ -0,0 +1,522 @@
+use crate::error::MatrixError;
+
+use std::{
+  fmt,
+  ops::{
+    Bound,
+    Index,
+    IndexMut,
+    Range,
+    RangeBounds
+  }
+};
+
+/// A borrowed, read-only block of a matrix of size `(rows, columns)`.
+///
+/// Element `(i, j)` of the view is stored at `i * row_stride + j * col_stride` in the
+/// borrowed data, so rows, columns and blocks can all be viewed without copying.
+pub struct MatrixView<'a, T: Copy> {
+  data: &'a [T],
+  rows: usize,
+  cols: usize,
+  row_stride: usize,
+  col_stride: usize
+}
+
+/// A borrowed, writable block of a matrix of size `(rows, columns)`.
+pub struct MatrixViewMut<'a, T: Copy> {
+  data: &'a mut [T],
+  rows: usize,
+  cols: usize,
+  row_stride: usize,
+  col_stride: usize
+}
+
+/// Returns the range of `data` covered by a `rows x cols` block starting at `start`.
+pub(crate) fn view_range(start: usize, rows: usize, cols: usize, row_stride: usize, col_stride: usize) -> Range<usize> {
+  if rows == 0 || cols == 0 {
+    return start..start;
+  }
+
+  start..(start + ((rows - 1) * row_stride) + ((cols - 1) * col_stride) + 1)
+}
+
+/// Resolves `range` against a dimension of length `len`, panicking if it falls outside of it.
+pub(crate) fn resolve_range<R: RangeBounds<usize>>(range: R, len: usize) -> Range<usize> {
+  let start = match range.start_bound() {
+    Bound::Included(&s) => s,
+    Bound::Excluded(&s) => s + 1,
+    Bound::Unbounded => 0
+  };
+  let end = match range.end_bound() {
+    Bound::Included(&e) => e + 1,
+    Bound::Excluded(&e) => e,
+    Bound::Unbounded => len
+  };
+  if (start > end) || (end > len) {
+    panic!("Range {}..{} is out of bounds for a dimension of length {}", start, end, len);
+  }
+
+  start..end
+}
+
+/// Returns the `(start, end)` ranges of the pieces of a dimension of length `len` split at `splits`.
+pub(crate) fn split_ranges(splits: &[usize], len: usize) -> Result<Vec<(usize, usize)>, MatrixError> {
+  let mut ranges: Vec<(usize, usize)> = Vec::with_capacity(splits.len() + 1);
+  let mut start = 0;
+  for index in splits.iter() {
+    if (*index <= start) || (*index >= len) {
+      return Err(MatrixError::InvalidSplit { index: *index, len });
+    }
+    ranges.push((start, *index));
+    start = *index;
+  }
+  ranges.push((start, len));
+
+  Ok(ranges)
+}
+
+/// Panics if the block `(row, col)` of size `(rows, cols)` does not fit in a matrix of size `size`.
+pub(crate) fn check_block(size: (usize, usize), row: usize, col: usize, rows: usize, cols: usize) {
+  if (row + rows > size.0) || (col + cols > size.1) {
+    panic!("Cannot view a block of size ({}, {}) at ({}, {}) in a matrix of size ({}, {})",
+           rows, cols, row, col, size.0, size.1);
+  }
+}
+
+impl<'a, T: Copy> MatrixView<'a, T> {
+  pub(crate) fn new(data: &'a [T], rows: usize, cols: usize, row_stride: usize, col_stride: usize) -> Self {
+
+    MatrixView { data, rows, cols, row_stride, col_stride }
+  }
+
+  pub fn size(&self) -> (usize, usize) {
+
+    (self.rows, self.cols)
+  }
+
+  /// Returns the value at `(i, j)`, or `None` if the index is out of bounds.
+  pub fn get(&self, i: usize, j: usize) -> Option<&'a T> {
+    if (i >= self.rows) || (j >= self.cols) {
+      return None;
+    }
+
+    self.data.get((i * self.row_stride) + (j * self.col_stride))
+  }
+
+  /// Creates an iterator over the values of the view in row-major order.
+  pub fn iter(&self) -> MatrixViewIterator<'a, T> {
+
+    MatrixViewIterator { inner: *self, idx: 0, end: self.rows * self.cols }
+  }
+
+  /// Creates an iterator over the values of the view and their `(row, column)` index.
+  pub fn indexed_iter(&self) -> IndexedIterator<'a, T> {
+
+    IndexedIterator { inner: self.iter() }
+  }
+
+  /// Creates an iterator over the rows of the view as `1 x columns` views.
+  pub fn rows(&self) -> MatrixRows<'a, T> {
+
+    MatrixRows { inner: *self, idx: 0, end: self.rows }
+  }
+
+  /// Creates an iterator over the columns of the view as `rows x 1` views.
+  pub fn cols(&self) -> MatrixCols<'a, T> {
+
+    MatrixCols { inner: *self, idx: 0, end: self.cols }
+  }
+
+  /// Borrows the block of size `(rows, cols)` starting at `(row, col)` of this view.
+  pub fn view(&self, row: usize, col: usize, rows: usize, cols: usize) -> MatrixView<'a, T> {
+    check_block(self.size(), row, col, rows, cols);
+    let start = (row * self.row_stride) + (col * self.col_stride);
+    let range = view_range(start, rows, cols, self.row_stride, self.col_stride);
+
+    MatrixView::new(&self.data[range], rows, cols, self.row_stride, self.col_stride)
+  }
+
+  /// Returns a view of the transpose without copying any values.
+  pub fn transpose(&self) -> MatrixView<'a, T> {
+
+    MatrixView::new(self.data, self.cols, self.rows, self.col_stride, self.row_stride)
+  }
+
+  pub fn t(&self) -> MatrixView<'a, T> {
+
+    self.transpose()
+  }
+
+  /// Copies the values of the view into a `Vec` in row-major order.
+  pub fn to_vec(&self) -> Vec<T> {
+
+    self.iter().collect()
+  }
+}
+
+impl<'a, T: Copy> Clone for MatrixView<'a, T> {
+  fn clone(&self) -> Self {
+
+    *self
+  }
+}
+
+impl<'a, T: Copy> Copy for MatrixView<'a, T> {}
+
+impl<'a, T: Copy> MatrixViewMut<'a, T> {
+  pub(crate) fn new(data: &'a mut [T], rows: usize, cols: usize, row_stride: usize, col_stride: usize) -> Self {
+
+    MatrixViewMut { data, rows, cols, row_stride, col_stride }
+  }
+
+  pub fn size(&self) -> (usize, usize) {
+
+    (self.rows, self.cols)
+  }
+
+  /// Reborrows the block as a read-only [MatrixView].
+  pub fn as_view(&self) -> MatrixView<'_, T> {
+
+    MatrixView::new(self.data, self.rows, self.cols, self.row_stride, self.col_stride)
+  }
+
+  /// Returns the value at `(i, j)`, or `None` if the index is out of bounds.
+  pub fn get(&self, i: usize, j: usize) -> Option<&T> {
+    if (i >= self.rows) || (j >= self.cols) {
+      return None;
+    }
+
+    self.data.get((i * self.row_stride) + (j * self.col_stride))
+  }
+
+  /// Returns a mutable reference to the value at `(i, j)`, or `None` if the index is out of bounds.
+  pub fn get_mut(&mut self, i: usize, j: usize) -> Option<&mut T> {
+    if (i >= self.rows) || (j >= self.cols) {
+      return None;
+    }
+
+    self.data.get_mut((i * self.row_stride) + (j * self.col_stride))
+  }
+
+  /// Creates an iterator over the values of the view in row-major order.
+  pub fn iter(&self) -> MatrixViewIterator<'_, T> {
+
+    self.as_view().iter()
+  }
+
+  /// Borrows the block of size `(rows, cols)` starting at `(row, col)` of this view.
+  pub fn view_mut(&mut self, row: usize, col: usize, rows: usize, cols: usize) -> MatrixViewMut<'_, T> {
+    check_block(self.size(), row, col, rows, cols);
+    let start = (row * self.row_stride) + (col * self.col_stride);
+    let range = view_range(start, rows, cols, self.row_stride, self.col_stride);
+
+    MatrixViewMut::new(&mut self.data[range], rows, cols, self.row_stride, self.col_stride)
+  }
+
+  /// Sets every value of the view to `val`.
+  pub fn fill(&mut self, val: T) {
+    self.apply(|_| val);
+  }
+
+  /// Replaces every value `x` of the view with `f(x)`.
+  pub fn apply<F: FnMut(T) -> T>(&mut self, mut f: F) {
+    for i in 0..self.rows {
+      for j in 0..self.cols {
+        let idx = (i * self.row_stride) + (j * self.col_stride);
+        self.data[idx] = f(self.data[idx]);
+      }
+    }
+  }
+
+  /// Copies the values of `other` into this view.
+  pub fn copy_from(&mut self, other: &MatrixView<'_, T>) -> Result<(), String> {
+    if self.size() != other.size() {
+      let s = format!("Cannot copy a view of size ({}, {}) into a view of size ({}, {})",
+                              other.rows, other.cols, self.rows, self.cols);
+      return Err(s);
+    }
+
+    for i in 0..self.rows {
+      for j in 0..self.cols {
+        self[(i, j)] = other[(i, j)];
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Copies the values of the view into a `Vec` in row-major order.
+  pub fn to_vec(&self) -> Vec<T> {
+
+    self.iter().collect()
+  }
+}
+
+pub struct MatrixViewIterator<'a, T: Copy> {
+  inner: MatrixView<'a, T>,
+  idx: usize,
+  end: usize
+}
+
+impl<'a, T: Copy> MatrixViewIterator<'a, T> {
+  fn index_of(&self, idx: usize) -> (usize, usize) {
+
+    (idx / self.inner.cols, idx % self.inner.cols)
+  }
+}
+
+impl<'a, T: Copy> Iterator for MatrixViewIterator<'a, T> {
+  type Item = T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.idx >= self.end {
+      return None;
+    }
+
+    let val = self.inner[self.index_of(self.idx)];
+    self.idx += 1;
+    Some(val)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+
+    (self.end - self.idx, Some(self.end - self.idx))
+  }
+}
+
+impl<'a, T: Copy> DoubleEndedIterator for MatrixViewIterator<'a, T> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    if self.idx >= self.end {
+      return None;
+    }
+
+    self.end -= 1;
+    Some(self.inner[self.index_of(self.end)])
+  }
+}
+
+impl<'a, T: Copy> ExactSizeIterator for MatrixViewIterator<'a, T> {}
+
+/// An iterator over the values of a matrix and their `(row, column)` index.
+pub struct IndexedIterator<'a, T: Copy> {
+  inner: MatrixViewIterator<'a, T>
+}
+
+impl<'a, T: Copy> Iterator for IndexedIterator<'a, T> {
+  type Item = ((usize, usize), T);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let index = self.inner.index_of(self.inner.idx);
+
+    self.inner.next().map(|val| (index, val))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+
+    self.inner.size_hint()
+  }
+}
+
+impl<'a, T: Copy> DoubleEndedIterator for IndexedIterator<'a, T> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    let val = self.inner.next_back()?;
+
+    Some((self.inner.index_of(self.inner.end), val))
+  }
+}
+
+impl<'a, T: Copy> ExactSizeIterator for IndexedIterator<'a, T> {}
+
+/// An iterator over mutable references to the values of a matrix in row-major order.
+///
+/// The storage is split into lines, one per column of a column-major matrix or a single one
+/// otherwise, and the iterator takes a value from each line in turn.
+pub struct MatrixIterMut<'a, T> {
+  lines: Vec<std::slice::IterMut<'a, T>>,
+  idx: usize,
+  end: usize
+}
+
+impl<'a, T> MatrixIterMut<'a, T> {
+  /// Iterates over `data`, stored as `lines` interleaved lines of equal length.
+  pub(crate) fn new(data: &'a mut [T], lines: usize) -> Self {
+    let end = data.len();
+    let len = end / lines.max(1);
+
+    MatrixIterMut { lines: data.chunks_mut(len.max(1)).map(|line| line.iter_mut()).collect(), idx: 0, end }
+  }
+}
+
+impl<'a, T> Iterator for MatrixIterMut<'a, T> {
+  type Item = &'a mut T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.idx >= self.end {
+      return None;
+    }
+
+    let line = self.idx % self.lines.len();
+    self.idx += 1;
+    self.lines[line].next()
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+
+    (self.end - self.idx, Some(self.end - self.idx))
+  }
+}
+
+impl<'a, T> DoubleEndedIterator for MatrixIterMut<'a, T> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    if self.idx >= self.end {
+      return None;
+    }
+
+    self.end -= 1;
+    let line = self.end % self.lines.len();
+    self.lines[line].next_back()
+  }
+}
+
+impl<'a, T> ExactSizeIterator for MatrixIterMut<'a, T> {}
+
+/// An iterator over the rows of a matrix as `1 x columns` views.
+pub struct MatrixRows<'a, T: Copy> {
+  inner: MatrixView<'a, T>,
+  idx: usize,
+  end: usize
+}
+
+impl<'a, T: Copy> Iterator for MatrixRows<'a, T> {
+  type Item = MatrixView<'a, T>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.idx >= self.end {
+      return None;
+    }
+
+    let row = self.inner.view(self.idx, 0, 1, self.inner.cols);
+    self.idx += 1;
+    Some(row)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+
+    (self.end - self.idx, Some(self.end - self.idx))
+  }
+}
+
+impl<'a, T: Copy> DoubleEndedIterator for MatrixRows<'a, T> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    if self.idx >= self.end {
+      return None;
+    }
+
+    self.end -= 1;
+    Some(self.inner.view(self.end, 0, 1, self.inner.cols))
+  }
+}
+
+impl<'a, T: Copy> ExactSizeIterator for MatrixRows<'a, T> {}
+
+/// An iterator over the columns of a matrix as `rows x 1` views.
+pub struct MatrixCols<'a, T: Copy> {
+  inner: MatrixView<'a, T>,
+  idx: usize,
+  end: usize
+}
+
+impl<'a, T: Copy> Iterator for MatrixCols<'a, T> {
+  type Item = MatrixView<'a, T>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.idx >= self.end {
+      return None;
+    }
+
+    let col = self.inner.view(0, self.idx, self.inner.rows, 1);
+    self.idx += 1;
+    Some(col)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+
+    (self.end - self.idx, Some(self.end - self.idx))
+  }
+}
+
+impl<'a, T: Copy> DoubleEndedIterator for MatrixCols<'a, T> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    if self.idx >= self.end {
+      return None;
+    }
+
+    self.end -= 1;
+    Some(self.inner.view(0, self.end, self.inner.rows, 1))
+  }
+}
+
+impl<'a, T: Copy> ExactSizeIterator for MatrixCols<'a, T> {}
+
+impl<'a, T: Copy> Index<(usize, usize)> for MatrixView<'a, T> {
+  type Output = T;
+
+  fn index(&self, index: (usize, usize)) -> &Self::Output {
+    let (i, j) = index;
+    if (i >= self.rows) || (j >= self.cols) {
+      panic!("Index ({}, {}) is out of bounds for a view of size ({}, {})", i, j, self.rows, self.cols);
+    }
+
+    &self.data[(i * self.row_stride) + (j * self.col_stride)]
+  }
+}
+
+impl<'a, T: Copy> Index<(usize, usize)> for MatrixViewMut<'a, T> {
+  type Output = T;
+
+  fn index(&self, index: (usize, usize)) -> &Self::Output {
+    let (i, j) = index;
+    if (i >= self.rows) || (j >= self.cols) {
+      panic!("Index ({}, {}) is out of bounds for a view of size ({}, {})", i, j, self.rows, self.cols);
+    }
+
+    &self.data[(i * self.row_stride) + (j * self.col_stride)]
+  }
+}
+
+impl<'a, T: Copy> IndexMut<(usize, usize)> for MatrixViewMut<'a, T> {
+  fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+    let (i, j) = index;
+    if (i >= self.rows) || (j >= self.cols) {
+      panic!("Index ({}, {}) is out of bounds for a view of size ({}, {})", i, j, self.rows, self.cols);
+    }
+
+    &mut self.data[(i * self.row_stride) + (j * self.col_stride)]
+  }
+}
+
+impl<'a, T: Copy + fmt::Display> fmt::Display for MatrixView<'a, T> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let mut text: String = String::from("");
+    text.push_str(&format!("View at addr {:p}:\n", self.data));
+
+    for i in 0..self.rows {
+      text.push_str("[ ");
+      for j in 0..self.cols {
+        let s = format!("{:>4} ", self[(i, j)]);
+        text.push_str(&s);
+      }
+      text.push_str("]\n");
+    }
+
+    write!(f, "{}", text)
+  }
+}
+
+impl<'a, T: Copy + fmt::Display> fmt::Display for MatrixViewMut<'a, T> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+
+    write!(f, "{}", self.as_view())
+  }
+}
@@ -4,10 +4,21 @@ pub mod macros;
 
 // pub use matrix::{*};
 // pub use float_matrix::{*};
+// The macros are exported at the crate root, so this only re-exports whatever else `macros` gains.
+#[allow(unused_imports)]
 pub use macros::{*};
 
+pub use gauss_core::banded::{*};
+pub use gauss_core::error::{*};
+pub use gauss_core::lattice::{*};
+pub use gauss_core::layout::{*};
 pub use gauss_core::matrix::{*};
 pub use gauss_core::float_matrix::{*};
+pub use gauss_core::parallel::{*};
+pub use gauss_core::sparse::{*};
+pub use gauss_core::static_matrix::{*};
+pub use gauss_core::vector::{*};
+pub use gauss_core::view::{*};
 
 pub use gauss_macros::example;
 
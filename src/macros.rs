@@ -76,4 +76,42 @@ macro_rules! mat {
       u.into()
     }
   };
-}
\ No newline at end of file
+}
+
+/// Builds a [FloatMatrix] from a grid of blocks, demarcating each block row with a semi-colon.
+///
+/// The blocks are borrowed and the macro returns the `Result` of [FloatMatrix::from_blocks]:
+///
+/// ```
+/// use gauss as gs;
+/// use gs::{FloatMatrix, float_block};
+///
+/// let a: FloatMatrix<f32> = FloatMatrix::new(2, 2);
+/// let b: FloatMatrix<f32> = FloatMatrix::new(2, 1);
+/// let i: FloatMatrix<f32> = FloatMatrix::<f32>::identity(3);
+///
+/// // [ A | b ]
+/// // [   I   ]
+/// let mat = float_block![
+///     a, b;
+///     i;
+/// ].unwrap();
+///
+/// assert_eq!(mat.size(), (5, 3));
+/// ```
+#[macro_export]
+macro_rules! float_block {
+  [$($($x:expr),+;)+] => {
+    FloatMatrix::from_blocks(&[$(&[$(&$x),+][..]),+])
+  };
+}
+
+/// Builds an integer [Matrix] from a grid of blocks, demarcating each block row with a semi-colon.
+///
+/// The blocks are borrowed and the macro returns the `Result` of [Matrix::from_blocks].
+#[macro_export]
+macro_rules! block {
+  [$($($x:expr),+;)+] => {
+    Matrix::from_blocks(&[$(&[$(&$x),+][..]),+])
+  };
+}
@@ -1,10 +1,28 @@
 
 use gauss as gs;
 use gs::example;
-use gs::{Matrix, FloatMatrix};
-use gs::float_mat;
+use gs::{Matrix, FloatMatrix, SMatrix, Vector, RowVector, MatrixView, Layout};
+use gs::{CooMatrix, CsrMatrix, CscMatrix};
+use gs::{TridiagonalMatrix, BandedMatrix};
+use gs::MatrixError;
+use gs::{parallel_threshold, with_parallel_threshold};
+use gs::{float_mat, float_block, block};
 // use gs::mat;
 
+/// Returns `len` deterministic values in `-(modulus / 2)..=(modulus / 2)` for the large products.
+fn fixture(len: usize, step: usize, modulus: usize) -> Vec<i64> {
+
+  (0..len).map(|x| ((x * step) % modulus) as i64 - (modulus / 2) as i64).collect()
+}
+
+/// Multiplies the row-major `m x k` matrix `a` by the row-major `k x n` matrix `b` with the
+/// textbook triple loop, adding the terms in order.
+fn naive_product<T>(a: &[T], b: &[T], m: usize, k: usize, n: usize) -> Vec<T>
+  where T: num::Zero + std::ops::Mul<Output = T> + Copy {
+
+  (0..(m * n)).map(|x| (0..k).fold(T::zero(), |sum, p| sum + (a[((x / n) * k) + p] * b[(p * n) + (x % n)]))).collect()
+}
+
 #[test]
 fn float_test() {
   // Test 1
@@ -142,4 +160,1120 @@ fn common_test() {
   let i: FloatMatrix<f32> = FloatMatrix::<f32>::identity(3);
   let j = Matrix::<u8>::identity(3);
   println!("{i}\n{j}");
-}
\ No newline at end of file
+}
+
+#[test]
+fn static_matrix_test() {
+  let a: SMatrix<f64, 2, 3> = SMatrix::from_array([
+    [1.0, 2.0, 3.0],
+    [4.0, 5.0, 6.0],
+  ]);
+  let b: SMatrix<f64, 3, 2> = a.transpose();
+  let c: SMatrix<f64, 2, 2> = a * b;
+
+  println!("{}", c);
+  assert_eq!(c.into_array(), [[14.0, 32.0], [32.0, 77.0]]);
+  assert_eq!(c * SMatrix::identity(), c);
+  assert_eq!((c - c).iter().sum::<f64>(), 0.0);
+
+  let dynamic: FloatMatrix<f64> = c.into();
+  assert_eq!(dynamic.size(), (2, 2));
+  assert_eq!(dynamic[(1, 0)], 32.0);
+
+  let back: SMatrix<f64, 2, 2> = dynamic.try_into().unwrap();
+  assert_eq!(back, c);
+
+  let wrong: Result<SMatrix<f64, 3, 3>, String> = FloatMatrix::<f64>::new(2, 2).try_into();
+  assert!(wrong.is_err());
+
+  let mut m: Matrix<i32> = Matrix::new(2, 2);
+  m[(0, 1)] = 7;
+  let s: SMatrix<i32, 2, 2> = m.try_into().unwrap();
+  assert_eq!(s[(0, 1)], 7);
+  let m: Matrix<i32> = (s + s).into();
+  assert_eq!(m[(0, 1)], 14);
+}
+
+#[test]
+fn vector_test() {
+  let u: Vector<f64> = Vector::from_vec(vec![1.0, 0.0, 0.0]);
+  let v: Vector<f64> = Vector::from_vec(vec![0.0, 1.0, 0.0]);
+
+  let w = u.cross(&v).unwrap();
+  assert_eq!(w.iter().collect::<Vec<f64>>(), vec![0.0, 0.0, 1.0]);
+  assert_eq!(u.dot(&v).unwrap(), 0.0);
+  assert!(u.cross(&Vector::new(2)).is_err());
+
+  let x: Vector<f64> = Vector::from_vec(vec![3.0, 4.0]);
+  assert_eq!(x.norm(), 5.0);
+  assert_eq!(x.normalize().unwrap().iter().collect::<Vec<f64>>(), vec![0.6, 0.8]);
+  assert!(Vector::<f64>::new(2).normalize().is_err());
+
+  let outer = x.outer(&u);
+  assert_eq!(outer.size(), (2, 3));
+  assert_eq!(outer[(1, 0)], 4.0);
+  assert_eq!((x.clone() * u.t()).size(), (2, 3));
+
+  let m: FloatMatrix<f64> = float_mat![
+    1.0, 2.0;
+    3.0, 4.0;
+    5.0, 6.0;
+  ];
+  let mx = (m.clone() * x.clone()).unwrap();
+  assert_eq!(mx.iter().collect::<Vec<f64>>(), vec![11.0, 25.0, 39.0]);
+  assert!((m.clone() * u.clone()).is_err());
+
+  let r: RowVector<f64> = RowVector::from_vec(vec![1.0, 1.0, 1.0]);
+  let rm = (r.clone() * m.clone()).unwrap();
+  assert_eq!(rm.iter().collect::<Vec<f64>>(), vec![9.0, 12.0]);
+  assert_eq!((r * mx.clone()).unwrap(), 75.0);
+
+  let col: FloatMatrix<f64> = mx.into();
+  assert_eq!(col.size(), (3, 1));
+  let back: Vector<f64> = col.try_into().unwrap();
+  println!("{}", back);
+  assert!(Vector::try_from(m).is_err());
+}
+
+#[test]
+fn view_test() {
+  let mut mat: FloatMatrix<f64> = float_mat![
+    1.0, 2.0, 3.0, 4.0;
+    5.0, 6.0, 7.0, 8.0;
+    9.0, 10.0, 11.0, 12.0;
+  ];
+
+  let block = mat.view(1, 1, 2, 2);
+  assert_eq!(block.size(), (2, 2));
+  assert_eq!(block.to_vec(), vec![6.0, 7.0, 10.0, 11.0]);
+  assert_eq!(block.t().to_vec(), vec![6.0, 10.0, 7.0, 11.0]);
+  assert_eq!(block.view(1, 0, 1, 2).to_vec(), vec![10.0, 11.0]);
+  println!("{}", block);
+
+  let col: MatrixView<f64> = mat.col_view(3);
+  assert_eq!(col.iter().collect::<Vec<f64>>(), vec![4.0, 8.0, 12.0]);
+  assert_eq!(mat.row_view(2).to_vec(), vec![9.0, 10.0, 11.0, 12.0]);
+
+  let owned: FloatMatrix<f64> = mat.view(0, 2, 3, 2).into();
+  assert_eq!(owned.size(), (3, 2));
+  assert_eq!(owned[(2, 1)], 12.0);
+
+  mat.view_mut(0, 0, 2, 2).fill(0.0);
+  mat.col_view_mut(3).apply(|x| x * 2.0);
+  assert_eq!(mat.row_view(0).to_vec(), vec![0.0, 0.0, 3.0, 8.0]);
+  assert_eq!(mat.row_view(1).to_vec(), vec![0.0, 0.0, 7.0, 16.0]);
+
+  let source = owned.view(0, 0, 1, 2);
+  mat.row_view_mut(2).view_mut(0, 1, 1, 2).copy_from(&source).unwrap();
+  assert_eq!(mat.row_view(2).to_vec(), vec![9.0, 3.0, 4.0, 24.0]);
+  assert!(mat.row_view_mut(2).copy_from(&source).is_err());
+
+  let mut int_mat: Matrix<i32> = Matrix::new(3, 3);
+  int_mat.view_mut(1, 1, 2, 2).fill(5);
+  let int_block: Matrix<i32> = int_mat.view(1, 0, 2, 3).into();
+  assert_eq!(int_block.iter().collect::<Vec<i32>>(), vec![0, 5, 5, 0, 5, 5]);
+}
+
+#[test]
+#[should_panic]
+fn view_out_of_bounds_test() {
+  let mat: FloatMatrix<f64> = FloatMatrix::new(2, 2);
+  let block = mat.view(0, 1, 2, 1);
+  let _ = block[(0, 1)];
+}
+
+#[test]
+fn slicing_test() {
+  let mut mat: FloatMatrix<f64> = float_mat![
+    1.0, 2.0, 3.0;
+    4.0, 5.0, 6.0;
+    7.0, 8.0, 9.0;
+    10.0, 11.0, 12.0;
+  ];
+
+  assert_eq!(mat.slice(1..3, ..).to_vec(), vec![4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+  assert_eq!(mat.slice(.., 1..=1).to_vec(), vec![2.0, 5.0, 8.0, 11.0]);
+  assert_eq!(mat.slice(2.., ..2).size(), (2, 2));
+  assert_eq!(mat.row_slice(1), Some(&[4.0, 5.0, 6.0][..]));
+  assert_eq!(mat.row(3).to_vec(), vec![10.0, 11.0, 12.0]);
+  assert_eq!(mat.col(2).to_vec(), vec![3.0, 6.0, 9.0, 12.0]);
+
+  mat.row_slice_mut(2).unwrap()[0] = 0.0;
+  mat.row_mut(0)[(0, 2)] = -3.0;
+  mat.slice_mut(3.., 1..).fill(1.0);
+  mat.col_mut(1).apply(|x| x + 1.0);
+  assert_eq!(mat.row(0).to_vec(), vec![1.0, 3.0, -3.0]);
+  assert_eq!(mat.row(2).to_vec(), vec![0.0, 9.0, 9.0]);
+  assert_eq!(mat.row(3).to_vec(), vec![10.0, 2.0, 1.0]);
+
+  assert_eq!(mat.get(1, 2), Some(&6.0));
+  assert_eq!(mat.get(1, 3), None);
+  assert_eq!(mat.get(4, 0), None);
+  *mat.get_mut(1, 1).unwrap() = 0.5;
+  assert_eq!(mat[(1, 1)], 0.5);
+  assert!(mat.get_mut(0, 3).is_none());
+
+  let mut int_mat: Matrix<i64> = Matrix::new(2, 3);
+  int_mat.row_slice_mut(1).unwrap().copy_from_slice(&[1, 2, 3]);
+  assert_eq!(int_mat.slice(1.., 1..).to_vec(), vec![2, 3]);
+  assert_eq!(int_mat.get(1, 3), None);
+}
+
+#[test]
+#[should_panic]
+fn column_out_of_bounds_test() {
+  let mat: Matrix<i32> = Matrix::new(2, 2);
+  // Column 2 used to silently read the first value of the next row.
+  let _ = mat[(0, 2)];
+}
+
+#[test]
+fn iterator_test() {
+  let mut mat: FloatMatrix<f64> = float_mat![
+    1.0, 2.0, 3.0;
+    4.0, 5.0, 6.0;
+  ];
+
+  assert_eq!(mat.iter().len(), 6);
+  assert_eq!(mat.iter().rev().collect::<Vec<f64>>(), vec![6.0, 5.0, 4.0, 3.0, 2.0, 1.0]);
+
+  let row_sums: Vec<f64> = mat.rows().map(|row| row.iter().sum()).collect();
+  assert_eq!(row_sums, vec![6.0, 15.0]);
+  let col_sums: Vec<f64> = mat.cols().map(|col| col.iter().sum()).collect();
+  assert_eq!(col_sums, vec![5.0, 7.0, 9.0]);
+  assert_eq!(mat.cols().len(), 3);
+  assert_eq!(mat.rows().next_back().unwrap().to_vec(), vec![4.0, 5.0, 6.0]);
+
+  let indexed: Vec<((usize, usize), f64)> = mat.indexed_iter().filter(|(_, val)| *val > 4.0).collect();
+  assert_eq!(indexed, vec![((1, 1), 5.0), ((1, 2), 6.0)]);
+  assert_eq!(mat.indexed_iter().next_back(), Some(((1, 2), 6.0)));
+
+  for val in mat.iter_mut() {
+    *val *= 10.0;
+  }
+  assert_eq!(mat[(1, 0)], 40.0);
+
+  let mut int_mat: Matrix<i32> = Matrix::new(3, 2);
+  for (idx, val) in int_mat.iter_mut().enumerate() {
+    *val = idx as i32;
+  }
+  let diagonal: Vec<i32> = int_mat.indexed_iter().filter(|((i, j), _)| i == j).map(|(_, val)| val).collect();
+  assert_eq!(diagonal, vec![0, 3]);
+  assert_eq!(int_mat.iter().rev().step_by(2).collect::<Vec<i32>>(), vec![5, 3, 1]);
+  assert_eq!(int_mat.cols().rev().map(|col| col.to_vec()).collect::<Vec<Vec<i32>>>(), vec![vec![1, 3, 5], vec![0, 2, 4]]);
+
+  // Mutable iteration follows the same row-major order as `iter`, whatever the layout.
+  let mut col_major: Matrix<i32> = Matrix::from_1d_vec_with_layout(vec![0; 6], 2, 3, Layout::ColMajor);
+  for (idx, val) in col_major.iter_mut().enumerate() {
+    *val = idx as i32;
+  }
+  assert_eq!(col_major.iter().collect::<Vec<i32>>(), vec![0, 1, 2, 3, 4, 5]);
+  assert_eq!(col_major.as_slice(), &[0, 3, 1, 4, 2, 5]);
+  assert_eq!(col_major.iter_mut().rev().map(|val| *val).collect::<Vec<i32>>(), vec![5, 4, 3, 2, 1, 0]);
+  let mut iter = col_major.iter_mut();
+  assert_eq!((iter.next().copied(), iter.next_back().copied(), iter.len()), (Some(0), Some(5), 4));
+  let mut floats: FloatMatrix<f64> = FloatMatrix::from_1d_vec_with_layout(vec![1.0, 4.0, 2.0, 5.0, 3.0, 6.0], 2, 3, Layout::ColMajor);
+  for (val, expected) in floats.iter_mut().zip([1.0, 2.0, 3.0, 4.0, 5.0, 6.0]) {
+    assert_eq!(*val, expected);
+  }
+}
+
+#[test]
+fn layout_test() {
+  // Fortran-ordered 2 x 3 matrix [1 2 3; 4 5 6]
+  let fortran = vec![1.0, 4.0, 2.0, 5.0, 3.0, 6.0];
+  let mat: FloatMatrix<f64> = FloatMatrix::from_1d_vec_with_layout(fortran.clone(), 2, 3, Layout::ColMajor);
+
+  assert_eq!(mat.layout(), Layout::ColMajor);
+  assert_eq!(mat[(0, 2)], 3.0);
+  assert_eq!(mat.iter().collect::<Vec<f64>>(), vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+  assert_eq!(mat.row_view(1).to_vec(), vec![4.0, 5.0, 6.0]);
+  assert_eq!(mat.slice(.., 1..).to_vec(), vec![2.0, 3.0, 5.0, 6.0]);
+  assert_eq!(mat.as_slice(), &fortran[..]);
+
+  let row_major = mat.to_layout(Layout::RowMajor);
+  assert_eq!(row_major.as_slice(), &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+  assert_eq!(row_major.row(1).to_vec(), vec![4.0, 5.0, 6.0]);
+
+  // Transposing only flips the layout flag.
+  let trans = row_major.clone().into_transpose();
+  assert_eq!(trans.size(), (3, 2));
+  assert_eq!(trans.layout(), Layout::ColMajor);
+  assert_eq!(trans.as_slice(), row_major.as_slice());
+  assert_eq!(trans.iter().collect::<Vec<f64>>(), vec![1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+  // `t` flips the layout as well, while `transpose_row_major` reorders the values.
+  assert_eq!(row_major.t().layout(), Layout::ColMajor);
+  assert_eq!(row_major.t().as_slice(), row_major.as_slice());
+  assert_eq!(trans.t().layout(), Layout::RowMajor);
+  let reordered = row_major.transpose_row_major();
+  assert_eq!(reordered.layout(), Layout::RowMajor);
+  assert_eq!(reordered.as_slice(), &[1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+  assert_eq!(reordered.iter().collect::<Vec<f64>>(), row_major.t().iter().collect::<Vec<f64>>());
+  let ints: Matrix<i32> = Matrix::from_1d_vec(vec![1, 2, 3, 4, 5, 6], 2, 3);
+  assert_eq!(ints.t().layout(), Layout::ColMajor);
+  assert_eq!(ints.transpose_row_major().row_slice(0), Some(&[1, 4][..]));
+  assert_eq!(ints.t().row_slice(0), None);
+  assert_eq!(ints.t().row(0).to_vec(), vec![1, 4]);
+  assert_eq!(ints.t().t().row_slice(1), Some(&[4, 5, 6][..]));
+
+  // Mixed layouts interoperate in arithmetic.
+  let sum = (mat.clone() + row_major.clone()).unwrap();
+  assert_eq!(sum.iter().collect::<Vec<f64>>(), vec![2.0, 4.0, 6.0, 8.0, 10.0, 12.0]);
+  let product = (row_major * trans).unwrap();
+  assert_eq!(product.iter().collect::<Vec<f64>>(), vec![14.0, 32.0, 32.0, 77.0]);
+  assert_eq!(mat.into_iter().collect::<Vec<f64>>(), vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+  let int_mat: Matrix<i32> = Matrix::from_1d_vec_with_layout(vec![1, 3, 2, 4], 2, 2, Layout::ColMajor);
+  assert_eq!(int_mat.get(0, 1), Some(&2));
+  assert_eq!(int_mat.into_layout(Layout::RowMajor).as_slice(), &[1, 2, 3, 4]);
+}
+
+#[test]
+fn column_major_row_slice_test() {
+  // Rows of a column-major matrix are not contiguous, so they are only borrowed as views.
+  let mut mat: FloatMatrix<f64> = FloatMatrix::from_1d_vec(vec![1.0, 2.0, 3.0, 4.0], 2, 2).into_transpose();
+  assert!(mat.row_slice(0).is_none() && mat.row_slice_mut(0).is_none());
+  assert!(mat.row_slice(2).is_none());
+  assert_eq!(mat.row(0).to_vec(), vec![1.0, 3.0]);
+  mat.row_mut(1).fill(0.0);
+  assert_eq!(mat.iter().collect::<Vec<f64>>(), vec![1.0, 3.0, 0.0, 0.0]);
+
+  let cols = Matrix::from_1d_vec_with_layout(vec![1, 2, 3, 4, 5, 6], 2, 3, Layout::ColMajor);
+  assert_eq!(cols.row(1).to_vec(), vec![2, 4, 6]);
+}
+
+#[test]
+fn sparse_test() {
+  // [ 4 0 1 ]
+  // [ 0 0 2 ]
+  // [ 3 0 0 ]
+  let mut coo: CooMatrix<f64> = CooMatrix::new(3, 3);
+  coo.push(2, 0, 3.0);
+  coo.push(0, 2, 1.0);
+  coo.push(0, 0, 1.5);
+  coo.push(1, 2, 2.0);
+  coo.push(0, 0, 2.5);
+  assert_eq!(coo.nnz(), 5);
+
+  let csr = coo.to_csr();
+  assert_eq!(csr.nnz(), 4);
+  assert_eq!(csr.row_ptr(), &[0, 2, 3, 4]);
+  assert_eq!(csr.col_idx(), &[0, 2, 2, 0]);
+  assert_eq!(csr.get(0, 0), 4.0);
+  assert_eq!(csr.get(1, 1), 0.0);
+
+  let csc = coo.to_csc();
+  assert_eq!(csc.col_ptr(), &[0, 2, 2, 4]);
+  assert_eq!(csc.row_idx(), &[0, 2, 0, 1]);
+  assert_eq!(csc.to_csr().values(), csr.values());
+  assert_eq!(csr.to_csc().values(), csc.values());
+
+  let dense: FloatMatrix<f64> = csr.to_dense();
+  assert_eq!(dense.iter().collect::<Vec<f64>>(), vec![4.0, 0.0, 1.0, 0.0, 0.0, 2.0, 3.0, 0.0, 0.0]);
+  assert_eq!(CsrMatrix::from(&dense).values(), csr.values());
+  assert_eq!(csr.t().to_dense().iter().collect::<Vec<f64>>(), dense.t().iter().collect::<Vec<f64>>());
+  assert_eq!(csc.t().get(2, 1), 2.0);
+
+  assert_eq!(csr.mul_vec(&[1.0, 2.0, 3.0]).unwrap(), vec![7.0, 6.0, 3.0]);
+  assert_eq!(csc.mul_vec(&[1.0, 2.0, 3.0]).unwrap(), vec![7.0, 6.0, 3.0]);
+  assert!(csr.mul_vec(&[1.0]).is_err());
+
+  let expected = (dense.clone() * dense.clone()).unwrap().iter().collect::<Vec<f64>>();
+  assert_eq!(csr.mul_sparse(&csr).unwrap().to_dense().iter().collect::<Vec<f64>>(), expected);
+  assert_eq!(csc.mul_sparse(&csc).unwrap().to_dense().iter().collect::<Vec<f64>>(), expected);
+  assert_eq!(csr.mul_dense(&dense).unwrap().iter().collect::<Vec<f64>>(), expected);
+  assert_eq!(csc.mul_dense(&dense).unwrap().iter().collect::<Vec<f64>>(), expected);
+  assert_eq!((dense.clone() * CsrMatrix::from(&dense)).unwrap().iter().collect::<Vec<f64>>(), expected);
+
+  let wide: CsrMatrix<f64> = CsrMatrix::from(&FloatMatrix::<f64>::new(2, 3));
+  assert!(wide.mul_sparse(&wide).is_err());
+
+  // Large and almost entirely empty.
+  let n = 1_000_000;
+  let mut coo: CooMatrix<f64> = CooMatrix::with_capacity(n, n, 3 * n);
+  for i in 0..n {
+    coo.push(i, i, 2.0);
+    if i + 1 < n {
+      coo.push(i, i + 1, -1.0);
+      coo.push(i + 1, i, -1.0);
+    }
+  }
+  let laplacian: CscMatrix<f64> = coo.to_csc();
+  let ones = vec![1.0; n];
+  let y = laplacian.mul_vec(&ones).unwrap();
+  assert_eq!((y[0], y[n / 2], y[n - 1]), (1.0, 0.0, 1.0));
+
+  let int_coo: CooMatrix<i64> = CooMatrix::new(2, 2);
+  assert_eq!(int_coo.to_csr().nnz(), 0);
+}
+
+#[test]
+fn banded_test() {
+  // Second-difference matrix from a 1-D Poisson problem.
+  let n = 6;
+  let tri: TridiagonalMatrix<f64> = TridiagonalMatrix::new(vec![-1.0; n - 1], vec![2.0; n], vec![-1.0; n - 1]).unwrap();
+  let x: Vector<f64> = Vector::from_vec((1..=n).map(|i| i as f64).collect());
+  let b = tri.mul_vec(&x).unwrap();
+  assert_eq!(b.iter().collect::<Vec<f64>>(), vec![0.0, 0.0, 0.0, 0.0, 0.0, 7.0]);
+
+  let solved = tri.solve(&b).unwrap();
+  for (a, e) in solved.iter().zip(x.iter()) {
+    assert!((a - e).abs() < 1e-12);
+  }
+  assert_eq!(tri.get(3, 2), -1.0);
+  assert_eq!(tri.get(0, 3), 0.0);
+  assert!(TridiagonalMatrix::new(vec![1.0], vec![1.0, 1.0], vec![]).is_err());
+  assert!(tri.solve(&Vector::new(2)).is_err());
+  let pivoted = tri.to_banded().solve(&b).unwrap();
+  for (a, e) in pivoted.iter().zip(solved.iter()) {
+    assert!((a - e).abs() < 1e-12);
+  }
+
+  // Needs pivoting: the leading entry is zero.
+  let mut banded: BandedMatrix<f64> = BandedMatrix::new(5, 2, 1);
+  let values = [
+    (0, 0, 0.0), (0, 1, 2.0),
+    (1, 0, 1.0), (1, 1, 1.0), (1, 2, 3.0),
+    (2, 0, 4.0), (2, 1, 2.0), (2, 2, 1.0), (2, 3, 1.0),
+    (3, 1, 1.0), (3, 2, 5.0), (3, 3, 2.0), (3, 4, 1.0),
+    (4, 2, 1.0), (4, 3, 3.0), (4, 4, 6.0),
+  ];
+  for (i, j, val) in values {
+    banded.set(i, j, val);
+  }
+  assert_eq!(banded.bandwidth(), (2, 1));
+  assert_eq!(banded.get(4, 0), 0.0);
+
+  let x: Vector<f64> = Vector::from_vec(vec![1.0, -2.0, 3.0, 0.5, -1.0]);
+  let b = banded.mul_vec(&x).unwrap();
+  let dense_b = (banded.to_dense() * x.clone()).unwrap();
+  assert_eq!(b.iter().collect::<Vec<f64>>(), dense_b.iter().collect::<Vec<f64>>());
+
+  let lu = banded.lu().unwrap();
+  let solved = lu.solve(&b).unwrap();
+  for (a, e) in solved.iter().zip(x.iter()) {
+    assert!((a - e).abs() < 1e-12);
+  }
+
+  let round_trip = BandedMatrix::from_dense(&banded.to_dense(), 2, 1).unwrap();
+  assert_eq!(round_trip.to_dense().iter().collect::<Vec<f64>>(), banded.to_dense().iter().collect::<Vec<f64>>());
+
+  let singular: BandedMatrix<f64> = BandedMatrix::new(3, 1, 1);
+  assert!(singular.solve(&Vector::new(3)).is_err());
+}
+
+#[test]
+fn block_test() {
+  let a: FloatMatrix<f32> = float_mat![
+    2.0, 1.0;
+    1.0, 3.0;
+  ];
+  let b: FloatMatrix<f32> = float_mat![
+    5.0;
+    6.0;
+  ];
+  let i: FloatMatrix<f32> = FloatMatrix::<f32>::identity(2);
+
+  let augmented = FloatMatrix::hstack(&[&a, &b]).unwrap();
+  assert_eq!(augmented.size(), (2, 3));
+  assert_eq!(augmented.iter().collect::<Vec<f32>>(), vec![2.0, 1.0, 5.0, 1.0, 3.0, 6.0]);
+
+  let gauss_jordan = FloatMatrix::hstack(&[&a, &i]).unwrap();
+  assert_eq!(gauss_jordan.row(1).to_vec(), vec![1.0, 3.0, 0.0, 1.0]);
+
+  let stacked = FloatMatrix::vstack(&[&a, &i.t()]).unwrap();
+  assert_eq!(stacked.size(), (4, 2));
+  assert_eq!(stacked.col(1).to_vec(), vec![1.0, 3.0, 0.0, 1.0]);
+
+  let grid = float_block![
+    a, b;
+    b.t(), i.view(0, 0, 1, 1).into();
+  ].unwrap();
+  assert_eq!(grid.size(), (3, 3));
+  assert_eq!(grid.row(2).to_vec(), vec![5.0, 6.0, 1.0]);
+
+  assert_eq!(FloatMatrix::hstack(&[&a, &b.t()]).err(), Some(MatrixError::RowMismatch { expected: 2, found: 1 }));
+  assert_eq!(FloatMatrix::vstack(&[&a, &b]).err(), Some(MatrixError::ColumnMismatch { expected: 2, found: 1 }));
+  assert_eq!(FloatMatrix::<f32>::vstack(&[]).err(), Some(MatrixError::Empty));
+  assert_eq!(FloatMatrix::from_blocks(&[&[&a, &b], &[&a]]).err(), Some(MatrixError::ColumnMismatch { expected: 3, found: 2 }));
+
+  let blocks = grid.split(&[2], &[2]).unwrap();
+  assert_eq!(blocks.len(), 2);
+  assert_eq!(blocks[0][0].iter().collect::<Vec<f32>>(), a.iter().collect::<Vec<f32>>());
+  assert_eq!(blocks[0][1].iter().collect::<Vec<f32>>(), b.iter().collect::<Vec<f32>>());
+  assert_eq!(blocks[1][1].size(), (1, 1));
+  assert_eq!(grid.split(&[], &[1, 2]).unwrap()[0].len(), 3);
+  assert_eq!(grid.split(&[3], &[]).err(), Some(MatrixError::InvalidSplit { index: 3, len: 3 }));
+  assert!(grid.split(&[2, 1], &[]).is_err());
+
+  let mut m: Matrix<i32> = Matrix::new(1, 2);
+  m[(0, 1)] = 4;
+  let int_grid = block![
+    m, m;
+  ].unwrap();
+  assert_eq!(int_grid.iter().collect::<Vec<i32>>(), vec![0, 4, 0, 4]);
+}
+
+#[test]
+fn reshape_test() {
+  let mat: FloatMatrix<f64> = float_mat![
+    1.0, 2.0, 3.0;
+    4.0, 5.0, 6.0;
+  ];
+
+  let reshaped = mat.clone().reshape(3, 2).unwrap();
+  assert_eq!(reshaped.row(2).to_vec(), vec![5.0, 6.0]);
+  // Reshaping follows the logical row-major order, whatever the storage layout.
+  let from_transpose = mat.clone().into_transpose().reshape(2, 3).unwrap();
+  assert_eq!(from_transpose.iter().collect::<Vec<f64>>(), vec![1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+  assert_eq!(mat.clone().reshape(4, 2).err(), Some(MatrixError::SizeMismatch { expected: 8, found: 6 }));
+  assert_eq!(mat.clone().reshape(usize::MAX, 2).err(), Some(MatrixError::InvalidDimensions { rows: usize::MAX, cols: 2 }));
+  assert_eq!(Matrix::from_1d_vec(vec![1, 2], 1, 2).reshape(2, usize::MAX).err(), Some(MatrixError::InvalidDimensions { rows: 2, cols: usize::MAX }));
+  assert_eq!(FloatMatrix::try_from_1d_vec(vec![1.0, 2.0, 3.0], 2, 2).err(), Some(MatrixError::SizeMismatch { expected: 4, found: 3 }));
+
+  let mut m = mat.clone();
+  m.resize(3, 4, -1.0).unwrap();
+  assert_eq!(m.row(0).to_vec(), vec![1.0, 2.0, 3.0, -1.0]);
+  assert_eq!(m.row(2).to_vec(), vec![-1.0, -1.0, -1.0, -1.0]);
+  m.resize(1, 2, 0.0).unwrap();
+  assert_eq!(m.iter().collect::<Vec<f64>>(), vec![1.0, 2.0]);
+  assert!(m.resize(0, 2, 0.0).is_err());
+
+  let mut m = mat.clone().into_transpose();
+  m.insert_row(1, &[7.0, 8.0]).unwrap();
+  m.push_row(&[9.0, 10.0]).unwrap();
+  assert_eq!(m.size(), (5, 2));
+  assert_eq!(m.col(1).to_vec(), vec![4.0, 8.0, 5.0, 6.0, 10.0]);
+  assert_eq!(m.insert_row(7, &[0.0, 0.0]).err(), Some(MatrixError::IndexOutOfBounds { index: 7, len: 5 }));
+  assert!(m.push_row(&[1.0]).is_err());
+
+  m.insert_col(0, &[0.0, 1.0, 2.0, 3.0, 4.0]).unwrap();
+  assert_eq!(m.row(1).to_vec(), vec![1.0, 7.0, 8.0]);
+  assert_eq!(m.remove_col(1).unwrap(), vec![1.0, 7.0, 2.0, 3.0, 9.0]);
+  assert_eq!(m.remove_row(1).unwrap(), vec![1.0, 8.0]);
+  assert_eq!(m.size(), (4, 2));
+  assert_eq!(m.iter().collect::<Vec<f64>>(), vec![0.0, 4.0, 2.0, 5.0, 3.0, 6.0, 4.0, 10.0]);
+
+  m.swap_rows(0, 3);
+  m.swap_cols(0, 1);
+  assert_eq!(m.row(0).to_vec(), vec![10.0, 4.0]);
+  assert_eq!(m.row(3).to_vec(), vec![4.0, 0.0]);
+
+  let mut single: Matrix<i32> = Matrix::new(1, 3);
+  assert_eq!(single.remove_row(0).err(), Some(MatrixError::InvalidDimensions { rows: 0, cols: 3 }));
+  single.push_row(&[1, 2, 3]).unwrap();
+  single.swap_rows(0, 1);
+  assert_eq!(single.remove_row(1).unwrap(), vec![0, 0, 0]);
+  let single = single.reshape(3, 1).unwrap();
+  assert_eq!(single.iter().collect::<Vec<i32>>(), vec![1, 2, 3]);
+}
+
+#[test]
+#[should_panic]
+fn from_1d_vec_size_test() {
+  let _ = FloatMatrix::from_1d_vec(vec![1.0, 2.0, 3.0], 2, 2);
+}
+
+#[test]
+fn kron_test() {
+  let a = FloatMatrix::from_1d_vec(vec![1.0, 2.0, 3.0, 4.0], 2, 2);
+  let b = FloatMatrix::from_1d_vec(vec![0.0, 5.0, 6.0, 7.0], 2, 2);
+  let k = a.kron(&b);
+  assert_eq!(k.size(), (4, 4));
+  assert_eq!(k.iter().collect::<Vec<f64>>(), vec![0.0, 5.0, 0.0, 10.0,
+                                                  6.0, 7.0, 12.0, 14.0,
+                                                  0.0, 15.0, 0.0, 20.0,
+                                                  18.0, 21.0, 24.0, 28.0]);
+  let k = FloatMatrix::kron(&a.t(), &FloatMatrix::from_1d_vec(vec![1.0, 2.0], 1, 2));
+  assert_eq!(k.size(), (2, 4));
+  assert_eq!(k.row(1).to_vec(), vec![2.0, 4.0, 4.0, 8.0]);
+
+  let s = a.kron_sum(&b).unwrap();
+  assert_eq!(s.row(0).to_vec(), vec![1.0, 5.0, 2.0, 0.0]);
+  assert_eq!(s.row(3).to_vec(), vec![0.0, 3.0, 6.0, 11.0]);
+  let rect = FloatMatrix::from_1d_vec(vec![1.0, 2.0], 1, 2);
+  assert_eq!(a.kron_sum(&rect).err(), Some(MatrixError::NotSquare { rows: 1, cols: 2 }));
+
+  let o = FloatMatrix::outer(&[1.0, 2.0], &[3.0, 4.0, 5.0]).unwrap();
+  assert_eq!(o.row(1).to_vec(), vec![6.0, 8.0, 10.0]);
+  assert!(FloatMatrix::<f64>::outer(&[], &[1.0]).is_err());
+
+  let m = FloatMatrix::from_1d_vec(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 2, 3);
+  let v = m.vec();
+  assert_eq!(v.size(), (6, 1));
+  assert_eq!(v.iter().collect::<Vec<f64>>(), vec![1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+  let u = v.unvec(2, 3).unwrap();
+  assert_eq!(u.iter().collect::<Vec<f64>>(), m.iter().collect::<Vec<f64>>());
+  assert_eq!(m.unvec(3, 2).err(), Some(MatrixError::ColumnMismatch { expected: 1, found: 3 }));
+  assert_eq!(v.unvec(4, 2).err(), Some(MatrixError::SizeMismatch { expected: 8, found: 6 }));
+
+  let a: Matrix<i32> = Matrix::from_1d_vec(vec![1, -1, 0, 2], 2, 2);
+  let i: Matrix<i32> = Matrix::from_1d_vec(vec![1, 0, 0, 1], 2, 2);
+  assert_eq!(i.kron(&a).row(2).to_vec(), vec![0, 0, 1, -1]);
+  assert_eq!(a.kron_sum(&a).unwrap().row(0).to_vec(), vec![2, -1, -1, 0]);
+  assert_eq!(Matrix::outer(&[2, 3], &[4]).unwrap().iter().collect::<Vec<i32>>(), vec![8, 12]);
+  assert_eq!(a.vec().unvec(2, 2).unwrap().iter().collect::<Vec<i32>>(), vec![1, -1, 0, 2]);
+}
+
+#[test]
+fn pow_test() {
+  let fib: Matrix<u64> = Matrix::from_1d_vec(vec![1, 1, 1, 0], 2, 2);
+  assert_eq!(fib.pow(0).unwrap().iter().collect::<Vec<u64>>(), vec![1, 0, 0, 1]);
+  assert_eq!(fib.pow(10).unwrap().row(0).to_vec(), vec![89, 55]);
+  assert_eq!(fib.checked_pow(90).unwrap()[(0, 1)], 2880067194370816120);
+  assert_eq!(fib.checked_pow(100).err(), Some(MatrixError::Overflow { row: 0, col: 0 }));
+  assert_eq!(fib.pow_mod(1000, 1_000_000_007).unwrap()[(0, 1)], 517691607);
+
+  let neg: Matrix<i32> = Matrix::from_1d_vec(vec![-1, 2, 0, -3], 2, 2);
+  assert_eq!(neg.t().pow(3).unwrap().iter().collect::<Vec<i32>>(), vec![-1, 0, 26, -27]);
+  assert_eq!(neg.pow_mod(3, 5).unwrap().iter().collect::<Vec<i32>>(), vec![4, 1, 0, 3]);
+  assert_eq!(neg.pow_mod(0, 1).unwrap().iter().collect::<Vec<i32>>(), vec![0, 0, 0, 0]);
+
+  // Moduli above the square root of the maximum, checked against a wider type.
+  let big: Matrix<i32> = Matrix::from_1d_vec(vec![99_999, -7, 12_345, 31_337], 2, 2);
+  let wide: Matrix<i64> = Matrix::from_1d_vec(vec![99_999, -7, 12_345, 31_337], 2, 2);
+  assert_eq!(big.pow_mod(77, 100_000).unwrap().iter().map(i64::from).collect::<Vec<i64>>(),
+             wide.pow_mod(77, 100_000).unwrap().iter().collect::<Vec<i64>>());
+  let m = u64::MAX - 58;
+  let big: Matrix<u64> = Matrix::from_1d_vec(vec![u64::MAX, 3, m - 1, 1 << 63], 2, 2);
+  let wide: Matrix<u128> = Matrix::from_1d_vec(vec![u64::MAX as u128, 3, (m - 1) as u128, 1 << 63], 2, 2);
+  assert_eq!(big.pow_mod(1_000_003, m).unwrap().iter().map(u128::from).collect::<Vec<u128>>(),
+             wide.pow_mod(1_000_003, m as u128).unwrap().iter().collect::<Vec<u128>>());
+
+  let walks: Matrix<u8> = Matrix::from_1d_vec(vec![0, 1, 1, 1, 0, 1, 1, 1, 0], 3, 3);
+  assert_eq!(walks.checked_pow(4).unwrap().row(0).to_vec(), vec![6, 5, 5]);
+  assert!(walks.checked_pow(12).is_err());
+
+  let rect: Matrix<i32> = Matrix::new(2, 3);
+  assert_eq!(rect.pow(2).err(), Some(MatrixError::NotSquare { rows: 2, cols: 3 }));
+
+  let markov: FloatMatrix<f64> = FloatMatrix::from_1d_vec(vec![0.9, 0.1, 0.5, 0.5], 2, 2);
+  let p = markov.pow(50).unwrap();
+  assert!((p[(0, 0)] - (5.0 / 6.0)).abs() < 1e-12);
+  assert!((p[(1, 1)] - (1.0 / 6.0)).abs() < 1e-12);
+  assert_eq!(markov.pow(1).unwrap().iter().collect::<Vec<f64>>(), markov.iter().collect::<Vec<f64>>());
+  assert!(FloatMatrix::<f64>::new(1, 2).pow(3).is_err());
+}
+
+#[test]
+fn pow_mod_negative_modulus_test() {
+  let neg: Matrix<i32> = Matrix::from_1d_vec(vec![-1, 2, 0, -3], 2, 2);
+  // A negative modulus used to give values outside of `0..m`.
+  assert_eq!(neg.pow_mod(1, -5).err(), Some(MatrixError::InvalidModulus));
+  assert_eq!(neg.pow_mod(0, 0).err(), Some(MatrixError::InvalidModulus));
+}
+
+#[test]
+fn matrix_functions_test() {
+  let close = |a: &FloatMatrix<f64>, b: &[f64], tol: f64| {
+    a.iter().zip(b.iter()).all(|(x, y)| (x - y).abs() <= tol * (1.0 + y.abs()))
+  };
+
+  let diag: FloatMatrix<f64> = FloatMatrix::from_1d_vec(vec![1.0, 0.0, 0.0, -2.0], 2, 2);
+  assert!(close(&diag.expm().unwrap(), &[1f64.exp(), 0.0, 0.0, (-2f64).exp()], 1e-14));
+  let nilpotent: FloatMatrix<f64> = FloatMatrix::from_1d_vec(vec![0.0, 1.0, 0.0, 0.0], 2, 2);
+  assert!(close(&nilpotent.expm().unwrap(), &[1.0, 1.0, 0.0, 1.0], 1e-15));
+  assert!(close(&nilpotent.t().expm().unwrap(), &[1.0, 0.0, 1.0, 1.0], 1e-15));
+
+  let theta = 2.5;
+  let rotation: FloatMatrix<f64> = FloatMatrix::from_1d_vec(vec![0.0, -theta, theta, 0.0], 2, 2);
+  let (c, s) = (theta.cos(), theta.sin());
+  assert!(close(&rotation.expm().unwrap(), &[c, -s, s, c], 1e-14));
+  assert!(close(&rotation.expm().unwrap().logm().unwrap(), &[0.0, -theta, theta, 0.0], 1e-12));
+
+  // Moler and Van Loan's example, which defeats a truncated Taylor series.
+  let hard: FloatMatrix<f64> = FloatMatrix::from_1d_vec(vec![-49.0, 24.0, -64.0, 31.0], 2, 2);
+  let (e1, e17) = ((-1f64).exp(), (-17f64).exp());
+  let expected = [(-2.0 * e1) + (3.0 * e17), (1.5 * e1) - (1.5 * e17),
+                  (-4.0 * e1) + (4.0 * e17), (3.0 * e1) - (2.0 * e17)];
+  assert!(close(&hard.expm().unwrap(), &expected, 1e-12));
+
+  let spd: FloatMatrix<f64> = FloatMatrix::from_1d_vec(vec![4.0, 1.0, 0.5, 1.0, 3.0, 0.2, 0.5, 0.2, 2.0], 3, 3);
+  let root = spd.sqrtm().unwrap();
+  assert!(close(&(root.clone() * root).unwrap(), &spd.iter().collect::<Vec<f64>>(), 1e-13));
+  let log = spd.logm().unwrap();
+  assert!(close(&log.expm().unwrap(), &spd.iter().collect::<Vec<f64>>(), 1e-12));
+  let square: FloatMatrix<f64> = FloatMatrix::from_1d_vec(vec![4.0, 0.0, 0.0, 9.0], 2, 2);
+  assert!(close(&square.sqrtm().unwrap(), &[2.0, 0.0, 0.0, 3.0], 1e-15));
+  assert!(close(&square.logm().unwrap(), &[4f64.ln(), 0.0, 0.0, 9f64.ln()], 1e-13));
+  let identity: FloatMatrix<f64> = FloatMatrix::from_1d_vec(vec![1.0, 0.0, 0.0, 1.0], 2, 2);
+  assert!(close(&identity.logm().unwrap(), &[0.0; 4], 0.0));
+
+  let single: FloatMatrix<f32> = FloatMatrix::from_1d_vec(vec![0.5, 0.25, 0.0, 1.5], 2, 2);
+  let back = single.expm().unwrap().logm().unwrap();
+  assert!(back.iter().zip(single.iter()).all(|(x, y)| (x - y).abs() < 1e-5));
+
+  let singular: FloatMatrix<f64> = FloatMatrix::from_1d_vec(vec![1.0, 2.0, 2.0, 4.0], 2, 2);
+  assert_eq!(singular.sqrtm().err(), Some(MatrixError::Singular));
+  assert!(singular.logm().is_err());
+  let negative: FloatMatrix<f64> = FloatMatrix::from_1d_vec(vec![-1.0, 0.0, 0.0, 1.0], 2, 2);
+  assert!(negative.logm().is_err());
+  assert_eq!(FloatMatrix::<f64>::new(2, 3).expm().err(), Some(MatrixError::NotSquare { rows: 2, cols: 3 }));
+}
+
+#[test]
+fn structure_test() {
+  let i: FloatMatrix<f64> = FloatMatrix::identity(3);
+  assert_eq!(i.iter().collect::<Vec<f64>>(), vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]);
+  let j: Matrix<i64> = Matrix::identity(2);
+  assert_eq!(j.iter().collect::<Vec<i64>>(), vec![1, 0, 0, 1]);
+  assert!(i.is_diagonal() && i.is_symmetric(0.0) && i.is_upper_triangular() && i.is_lower_triangular());
+
+  let m = FloatMatrix::from_1d_vec(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0], 3, 3);
+  assert_eq!(m.trace().unwrap(), 15.0);
+  assert_eq!(m.t().diagonal(), vec![1.0, 5.0, 9.0]);
+  assert_eq!(m.tril(0).iter().collect::<Vec<f64>>(), vec![1.0, 0.0, 0.0, 4.0, 5.0, 0.0, 7.0, 8.0, 9.0]);
+  assert_eq!(m.tril(-1).iter().collect::<Vec<f64>>(), vec![0.0, 0.0, 0.0, 4.0, 0.0, 0.0, 7.0, 8.0, 0.0]);
+  assert_eq!(m.t().triu(1).iter().collect::<Vec<f64>>(), vec![0.0, 4.0, 7.0, 0.0, 0.0, 8.0, 0.0, 0.0, 0.0]);
+  assert!(m.triu(0).is_upper_triangular() && !m.triu(0).is_lower_triangular());
+  assert!(!m.tril(1).is_tridiagonal() && m.tril(1).triu(-1).is_tridiagonal());
+  assert!(!m.is_symmetric(1e-12));
+
+  let near = FloatMatrix::from_1d_vec(vec![2.0, 1.0 + 1e-10, 1.0, 3.0], 2, 2);
+  assert!(near.is_symmetric(1e-9) && !near.is_symmetric(1e-11));
+
+  let rect = FloatMatrix::from_1d_vec(vec![1.0, 2.0, 3.0, 0.0, 4.0, 5.0], 2, 3);
+  assert_eq!(rect.diagonal(), vec![1.0, 4.0]);
+  assert!(rect.is_upper_triangular() && !rect.is_square() && !rect.is_symmetric(1.0));
+  assert_eq!(rect.trace().err(), Some(MatrixError::NotSquare { rows: 2, cols: 3 }));
+  assert_eq!(rect.tril(1).row(0).to_vec(), vec![1.0, 2.0, 0.0]);
+
+  let d: Matrix<i32> = Matrix::from_diagonal(&[3, -1, 2]);
+  assert_eq!(d.trace().unwrap(), 4);
+  assert_eq!(d.row(1).to_vec(), vec![0, -1, 0]);
+  assert!(d.is_diagonal() && d.is_symmetric() && d.is_tridiagonal());
+  let s: Matrix<i32> = Matrix::from_1d_vec(vec![1, 2, 2, 1], 2, 2);
+  assert!(s.t().is_symmetric() && !s.is_upper_triangular());
+  assert!(!Matrix::from_1d_vec(vec![1, 2, 3, 1], 2, 2).is_symmetric());
+  assert_eq!(FloatMatrix::from_diagonal(&[2.0, 0.5]).diagonal(), vec![2.0, 0.5]);
+}
+
+#[test]
+fn overflow_test() {
+  let a: Matrix<u8> = Matrix::from_1d_vec(vec![200, 10, 0, 255], 2, 2);
+  let b: Matrix<u8> = Matrix::from_1d_vec(vec![100, 5, 1, 1], 2, 2);
+
+  assert_eq!(a.checked_add(&b).err(), Some(MatrixError::Overflow { row: 0, col: 0 }));
+  assert_eq!(a.wrapping_add(&b).unwrap().iter().collect::<Vec<u8>>(), vec![44, 15, 1, 0]);
+  assert_eq!(a.saturating_add(&b).unwrap().iter().collect::<Vec<u8>>(), vec![255, 15, 1, 255]);
+
+  assert_eq!(a.checked_sub(&b).err(), Some(MatrixError::Overflow { row: 1, col: 0 }));
+  assert_eq!(a.wrapping_sub(&b).unwrap().iter().collect::<Vec<u8>>(), vec![100, 5, 255, 254]);
+  assert_eq!(a.saturating_sub(&b).unwrap().iter().collect::<Vec<u8>>(), vec![100, 5, 0, 254]);
+  assert_eq!(b.t().checked_sub(&b.t()).unwrap().iter().collect::<Vec<u8>>(), vec![0, 0, 0, 0]);
+
+  let small: Matrix<u8> = Matrix::from_1d_vec(vec![1, 2, 3, 4], 2, 2);
+  assert_eq!(small.checked_mul(&small).unwrap().iter().collect::<Vec<u8>>(), vec![7, 10, 15, 22]);
+  assert_eq!(a.checked_mul(&b).err(), Some(MatrixError::Overflow { row: 0, col: 0 }));
+  assert_eq!(a.wrapping_mul(&b).unwrap().row(0).to_vec(), vec![42, 242]);
+  assert_eq!(a.saturating_mul(&b).unwrap().iter().collect::<Vec<u8>>(), vec![255, 255, 255, 255]);
+
+  let s: Matrix<i8> = Matrix::from_1d_vec(vec![100, 100, -100, 1], 2, 2);
+  let t: Matrix<i8> = Matrix::from_1d_vec(vec![1, 1, 1, -1], 2, 2);
+  assert_eq!(s.checked_mul(&t).err(), Some(MatrixError::Overflow { row: 0, col: 0 }));
+  assert_eq!(s.saturating_mul(&t).unwrap().iter().collect::<Vec<i8>>(), vec![127, 0, -99, -101]);
+  assert_eq!(s.wrapping_mul(&t).unwrap().iter().collect::<Vec<i8>>(), vec![-56, 0, -99, -101]);
+
+  let wide: Matrix<u8> = Matrix::new(2, 3);
+  assert_eq!(a.checked_add(&wide).err(), Some(MatrixError::ColumnMismatch { expected: 2, found: 3 }));
+  assert_eq!(wide.saturating_mul(&a).err(), Some(MatrixError::RowMismatch { expected: 3, found: 2 }));
+  assert_eq!(a.wrapping_mul(&wide).unwrap().size(), (2, 3));
+}
+
+#[test]
+fn widening_mul_test() {
+  let a: Matrix<i8> = Matrix::from_1d_vec(vec![127, -128, 3, 4], 2, 2);
+  let b: Matrix<i8> = Matrix::from_1d_vec(vec![127, 1, -128, 2], 2, 2);
+  let c: Matrix<i32> = a.widening_mul(&b).unwrap();
+  assert_eq!(c.iter().collect::<Vec<i32>>(), vec![32513, -129, -131, 11]);
+  let c: Matrix<i16> = a.t().widening_mul(&b).unwrap();
+  assert_eq!(c.row(0).to_vec(), vec![16129 - 384, 127 + 6]);
+
+  let x: Matrix<u8> = Matrix::from_1d_vec(vec![255; 6], 2, 3);
+  let y: Matrix<u8> = Matrix::from_1d_vec(vec![255; 6], 3, 2);
+  assert!(x.checked_mul(&y).is_err());
+  let z: Matrix<u32> = x.widening_mul(&y).unwrap();
+  assert_eq!(z.iter().collect::<Vec<u32>>(), vec![195075; 4]);
+  let z: Matrix<u64> = x.widening_mul(&y).unwrap();
+  assert_eq!(z[(1, 1)], 195075);
+
+  let i: Matrix<i32> = Matrix::from_1d_vec(vec![i32::MAX, i32::MIN], 1, 2);
+  let j: Matrix<i32> = Matrix::from_1d_vec(vec![i32::MAX, 1], 2, 1);
+  let k: Matrix<i64> = i.widening_mul(&j).unwrap();
+  assert_eq!(k[(0, 0)], (i32::MAX as i64 * i32::MAX as i64) + i32::MIN as i64);
+  assert_eq!(i.widening_mul::<i64>(&i).err(), Some(MatrixError::RowMismatch { expected: 2, found: 1 }));
+}
+
+#[test]
+fn normal_form_test() {
+  let copy = |m: &Matrix<i64>| Matrix::from_1d_vec(m.iter().collect(), m.size().0, m.size().1);
+  let values = |m: &Matrix<i64>| m.iter().collect::<Vec<i64>>();
+
+  let a: Matrix<i64> = Matrix::from_1d_vec(vec![2, 4, 4, -6, 6, 12, 10, -4, -16], 3, 3);
+  let hnf = a.hermite_normal_form().unwrap();
+  assert_eq!(values(&(copy(hnf.u()) * copy(&a)).unwrap()), values(hnf.h()));
+  assert_eq!(values(hnf.h()), vec![2, 4, 4, 0, 6, 0, 0, 0, 12]);
+  assert_eq!(hnf.rank(), 3);
+
+  let snf = a.smith_normal_form().unwrap();
+  let product = ((copy(snf.u()) * copy(&a)).unwrap() * copy(snf.v())).unwrap();
+  assert_eq!(values(&product), values(snf.d()));
+  assert_eq!(values(snf.d()), vec![2, 0, 0, 0, 6, 0, 0, 0, 12]);
+  assert_eq!(snf.invariant_factors(), vec![2, 6, 12]);
+
+  // Z^2 / <(2, 4), (6, 8), (4, 4)> is Z/2 x Z/4.
+  let rect: Matrix<i32> = Matrix::from_1d_vec(vec![2, 4, 6, 8, 4, 4], 3, 2);
+  let snf = rect.t().smith_normal_form().unwrap();
+  assert_eq!(snf.invariant_factors(), vec![2, 4]);
+  assert_eq!(snf.d().size(), (2, 3));
+  assert_eq!(snf.u().size(), (2, 2));
+  assert_eq!(snf.v().size(), (3, 3));
+  let hnf = rect.hermite_normal_form().unwrap();
+  assert_eq!(hnf.h().iter().collect::<Vec<i32>>(), vec![2, 0, 0, 4, 0, 0]);
+  assert_eq!(hnf.rank(), 2);
+
+  let singular: Matrix<i64> = Matrix::from_1d_vec(vec![0, -3, 6, 0, 2, -4], 2, 3);
+  let hnf = singular.hermite_normal_form().unwrap();
+  assert_eq!(values(hnf.h()), vec![0, 1, -2, 0, 0, 0]);
+  assert_eq!(values(&(copy(hnf.u()) * copy(&singular)).unwrap()), values(hnf.h()));
+  let snf = singular.smith_normal_form().unwrap();
+  assert_eq!(values(snf.d()), vec![1, 0, 0, 0, 0, 0]);
+  assert_eq!(snf.rank(), 1);
+
+  let zero: Matrix<i64> = Matrix::new(2, 2);
+  assert_eq!(zero.smith_normal_form().unwrap().rank(), 0);
+  assert_eq!(values(zero.hermite_normal_form().unwrap().u()), vec![1, 0, 0, 1]);
+
+  let big: Matrix<i8> = Matrix::from_1d_vec(vec![127, 125, 126, 127], 2, 2);
+  assert!(matches!(big.smith_normal_form().err(), Some(MatrixError::Overflow { .. })));
+
+  // Negating or dividing the minimum value reports the overflow instead of panicking.
+  let min: Matrix<i8> = Matrix::from_1d_vec(vec![-128, 0, 0, 1], 2, 2);
+  assert_eq!(min.hermite_normal_form().err(), Some(MatrixError::Overflow { row: 0, col: 0 }));
+  assert_eq!(min.smith_normal_form().err(), Some(MatrixError::Overflow { row: 1, col: 1 }));
+  let column: Matrix<i8> = Matrix::from_1d_vec(vec![-1, -128], 2, 1);
+  assert_eq!(column.hermite_normal_form().err(), Some(MatrixError::Overflow { row: 0, col: 0 }));
+}
+
+#[test]
+fn diophantine_test() {
+  let apply = |a: &Matrix<i64>, x: &[i64]| {
+    a.rows().map(|row| row.iter().zip(x.iter()).map(|(p, q)| p * q).sum::<i64>()).collect::<Vec<i64>>()
+  };
+
+  let a: Matrix<i64> = Matrix::from_1d_vec(vec![6, 10, 15], 1, 3);
+  let sol = a.solve_diophantine(&[1]).unwrap();
+  assert_eq!(apply(&a, sol.particular()), vec![1]);
+  assert_eq!(sol.kernel().len(), 2);
+  for k in sol.kernel() {
+    assert_eq!(apply(&a, k), vec![0]);
+  }
+  assert_eq!(apply(&a, &sol.solution(&[3, -7])), vec![1]);
+
+  let a: Matrix<i64> = Matrix::from_1d_vec(vec![2, 3, 1, 1, -1, 4, 3, 2, 5], 3, 3);
+  let sol = a.solve_diophantine(&[13, 5, 18]).unwrap();
+  assert_eq!(apply(&a, sol.particular()), vec![13, 5, 18]);
+  assert_eq!(sol.kernel().len(), 1);
+  let k = &sol.kernel()[0];
+  assert_eq!(k.iter().map(|x| x.abs()).collect::<Vec<i64>>(), vec![13, 7, 5]);
+  assert_eq!(a.solve_diophantine(&[13, 5, 17]).err(), Some(MatrixError::NoSolution));
+
+  // x + y = 1 and x - y = 0 only has the rational solution (1/2, 1/2).
+  let a: Matrix<i64> = Matrix::from_1d_vec(vec![1, 1, 1, -1], 2, 2);
+  assert_eq!(a.solve_diophantine(&[1, 0]).err(), Some(MatrixError::NoSolution));
+  let sol = a.t().solve_diophantine(&[4, 2]).unwrap();
+  assert_eq!(sol.particular(), &[3, 1]);
+  assert!(sol.kernel().is_empty());
+  assert_eq!(sol.solution(&[]), vec![3, 1]);
+
+  let tall: Matrix<i32> = Matrix::from_1d_vec(vec![2, 0, 0, 3, 2, 3], 3, 2);
+  assert_eq!(tall.solve_diophantine(&[4, 9, 13]).unwrap().particular(), &[2, 3]);
+  assert_eq!(tall.solve_diophantine(&[4, 9, 8]).err(), Some(MatrixError::NoSolution));
+  assert_eq!(tall.solve_diophantine(&[4, 9]).err(), Some(MatrixError::SizeMismatch { expected: 3, found: 2 }));
+}
+
+#[test]
+fn lll_test() {
+  let hnf = |m: &Matrix<i64>| m.hermite_normal_form().unwrap().h().iter().collect::<Vec<i64>>();
+
+  let basis: Matrix<i64> = Matrix::from_1d_vec(vec![1, 1, 1, -1, 0, 2, 3, 5, 6], 3, 3);
+  let reduced = basis.lll(0.75).unwrap();
+  assert_eq!(reduced.iter().collect::<Vec<i64>>(), vec![0, 1, 0, 1, 0, 1, -1, 0, 2]);
+  assert_eq!(hnf(&reduced), hnf(&basis));
+
+  // Checks |mu_ij| <= 1/2 and the Lovász condition in floating point.
+  let check = |m: &Matrix<i64>, delta: f64| {
+    let rows: Vec<Vec<f64>> = m.rows().map(|r| r.iter().map(|x| x as f64).collect()).collect();
+    let mut stars: Vec<Vec<f64>> = Vec::new();
+    let mut norms: Vec<f64> = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+      let mut star = row.clone();
+      let mut last = 0.0;
+      for j in 0..i {
+        let mu = row.iter().zip(stars[j].iter()).map(|(a, b)| a * b).sum::<f64>() / norms[j];
+        assert!(mu.abs() <= 0.5 + 1e-9);
+        star.iter_mut().zip(stars[j].iter()).for_each(|(s, b)| *s -= mu * b);
+        last = mu;
+      }
+      let norm = star.iter().map(|x| x * x).sum::<f64>();
+      if i > 0 {
+        assert!(norm >= ((delta - (last * last)) * norms[i - 1]) - 1e-9);
+      }
+      stars.push(star);
+      norms.push(norm);
+    }
+  };
+  check(&reduced, 0.75);
+
+  let skewed: Matrix<i64> = Matrix::from_1d_vec(vec![201, 37, 0, 1648, 297, 0, 5, 7, 11], 3, 3);
+  for delta in [0.3, 0.75, 0.99, 1.0] {
+    let reduced = skewed.t().lll(delta).unwrap();
+    check(&reduced, delta);
+    assert_eq!(hnf(&reduced), hnf(&skewed.t()));
+  }
+
+  // Finds the integer relation 3 + 5 - 8 = 0 from a knapsack-style basis.
+  let relation: Matrix<i64> = Matrix::from_1d_vec(vec![1, 0, 0, 3000, 0, 1, 0, 5000, 0, 0, 1, 8000], 3, 4);
+  let reduced = relation.lll(0.75).unwrap();
+  let first = reduced.row(0).to_vec();
+  assert_eq!(first[3], 0);
+  assert_eq!((3 * first[0]) + (5 * first[1]) + (8 * first[2]), 0);
+  assert_eq!(first.iter().map(|x| x.abs()).collect::<Vec<i64>>(), vec![1, 1, 1, 0]);
+
+  let dependent: Matrix<i32> = Matrix::from_1d_vec(vec![1, 2, 2, 4], 2, 2);
+  assert_eq!(dependent.lll(0.75).err(), Some(MatrixError::Singular));
+  let single: Matrix<i32> = Matrix::from_1d_vec(vec![-4, 6], 1, 2);
+  assert_eq!(single.lll(0.75).unwrap().row(0).to_vec(), vec![-4, 6]);
+}
+
+#[test]
+fn blocked_mul_test() {
+  // Sizes chosen to cross the row, shared-dimension and column block boundaries of the kernel.
+  let (m, k, n) = (70, 300, 530);
+  let a_vals = fixture(m * k, 7, 23);
+  let b_vals = fixture(k * n, 13, 19);
+  let naive = naive_product(&a_vals, &b_vals, m, k, n);
+
+  let a: Matrix<i64> = Matrix::from_1d_vec(a_vals.clone(), m, k);
+  let b: Matrix<i64> = Matrix::from_1d_vec(b_vals.clone(), k, n);
+  assert_eq!((a * b).unwrap().iter().collect::<Vec<i64>>(), naive);
+
+  let a: Matrix<i64> = Matrix::from_1d_vec(a_vals.clone(), m, k);
+  let bt: Matrix<i64> = Matrix::from_1d_vec(b_vals.clone(), k, n).to_layout(Layout::ColMajor);
+  assert_eq!((a * bt).unwrap().iter().collect::<Vec<i64>>(), naive);
+
+  let af: FloatMatrix<f64> = FloatMatrix::from_1d_vec(a_vals.iter().map(|x| *x as f64).collect(), m, k);
+  let bf: FloatMatrix<f64> = FloatMatrix::from_1d_vec(b_vals.iter().map(|x| *x as f64).collect(), k, n);
+  let product = (af.to_layout(Layout::ColMajor) * bf.to_layout(Layout::ColMajor)).unwrap();
+  assert_eq!(product.layout(), Layout::RowMajor);
+  assert_eq!(product.iter().collect::<Vec<f64>>(), naive.iter().map(|x| *x as f64).collect::<Vec<f64>>());
+}
+
+#[test]
+fn parallel_test() {
+  let (m, k, n) = (130, 90, 70);
+  let a_vals: Vec<f64> = fixture(m * k, 7, 23).iter().map(|x| *x as f64 / 7.0).collect();
+  let b_vals: Vec<f64> = fixture(k * n, 13, 19).iter().map(|x| *x as f64 / 3.0).collect();
+  let c_vals: Vec<f64> = fixture(m * n, 1, 17).iter().map(|x| *x as f64 / 11.0).collect();
+  let s_vals: Vec<f64> = fixture(n * n, 5, 29).iter().map(|x| *x as f64 / 2000.0).collect();
+  let run = || {
+    let a: FloatMatrix<f64> = FloatMatrix::from_1d_vec(a_vals.clone(), m, k);
+    let b: FloatMatrix<f64> = FloatMatrix::from_1d_vec(b_vals.clone(), k, n);
+    let c: FloatMatrix<f64> = FloatMatrix::from_1d_vec(c_vals.clone(), m, n);
+    let s: FloatMatrix<f64> = FloatMatrix::from_1d_vec(s_vals.clone(), n, n);
+    let product = (a.clone() * b.to_layout(Layout::ColMajor)).unwrap();
+    let sum = (product.clone() + c.to_layout(Layout::ColMajor)).unwrap();
+    let difference = (sum.clone() - c).unwrap();
+    let transposed = a.to_layout(Layout::ColMajor);
+    let exponential = s.expm().unwrap();
+    let ints: Vec<i64> = a_vals.iter().map(|x| (x * 7.0) as i64).collect();
+    let int_product = (Matrix::from_1d_vec(ints.clone(), m, k) * Matrix::from_1d_vec(ints, m, k).into_transpose()).unwrap();
+
+    (vec![product.iter().collect::<Vec<f64>>(), sum.iter().collect(), difference.iter().collect(),
+          transposed.as_slice().to_vec(), exponential.iter().collect()],
+     int_product.iter().collect::<Vec<i64>>())
+  };
+
+  // The threshold is only overridden on this thread, so other tests keep the global one.
+  let threshold = parallel_threshold();
+  let serial = with_parallel_threshold(usize::MAX, run);
+  let parallel = with_parallel_threshold(0, || {
+    assert_eq!(with_parallel_threshold(5, parallel_threshold), 5);
+    assert_eq!(parallel_threshold(), 0);
+    run()
+  });
+  assert_eq!(parallel_threshold(), threshold);
+  assert_eq!(with_parallel_threshold(0, || std::thread::spawn(parallel_threshold).join().unwrap()), threshold);
+
+  assert_eq!(serial.1, parallel.1);
+  for (x, y) in serial.0.iter().zip(parallel.0.iter()) {
+    assert_eq!(x.iter().map(|v| v.to_bits()).collect::<Vec<u64>>(), y.iter().map(|v| v.to_bits()).collect::<Vec<u64>>());
+  }
+  assert_eq!(serial.0[3], (0..k).flat_map(|j| (0..m).map(move |i| (i, j))).map(|(i, j)| a_vals[(i * k) + j]).collect::<Vec<f64>>());
+}
+
+#[test]
+fn simd_test() {
+  // Odd sizes leave remainders after every register tile and vector width.
+  let (m, k, n) = (37, 29, 43);
+  let a_vals: Vec<f64> = fixture(m * k, 7, 23).iter().map(|x| *x as f64 / 7.0).collect();
+  let b_vals: Vec<f64> = fixture(k * n, 13, 19).iter().map(|x| *x as f64 / 3.0).collect();
+  let naive = naive_product(&a_vals, &b_vals, m, k, n);
+
+  let a: FloatMatrix<f64> = FloatMatrix::from_1d_vec(a_vals.clone(), m, k);
+  let b: FloatMatrix<f64> = FloatMatrix::from_1d_vec(b_vals.clone(), k, n);
+  let product = (a * b).unwrap();
+  assert_eq!(product.iter().map(|v| v.to_bits()).collect::<Vec<u64>>(), naive.iter().map(|v| v.to_bits()).collect::<Vec<u64>>());
+
+  let a_vals: Vec<f32> = a_vals.iter().map(|v| *v as f32).collect();
+  let b_vals: Vec<f32> = b_vals.iter().map(|v| *v as f32).collect();
+  let naive = naive_product(&a_vals, &b_vals, m, k, n);
+  let a: FloatMatrix<f32> = FloatMatrix::from_1d_vec(a_vals, m, k);
+  let b: FloatMatrix<f32> = FloatMatrix::from_1d_vec(b_vals, k, n);
+  let product = (a.clone() * b.to_layout(Layout::ColMajor)).unwrap();
+  assert_eq!(product.iter().map(|v| v.to_bits()).collect::<Vec<u32>>(), naive.iter().map(|v| v.to_bits()).collect::<Vec<u32>>());
+
+  let sum = (a.clone() + a.clone()).unwrap();
+  let difference = (sum.clone() - a.to_layout(Layout::ColMajor)).unwrap();
+  assert_eq!(sum.iter().collect::<Vec<f32>>(), a.iter().map(|v| v * 2.0).collect::<Vec<f32>>());
+  assert_eq!(difference.iter().collect::<Vec<f32>>(), a.iter().collect::<Vec<f32>>());
+
+  // Small integers keep every sum exact, whatever the order of the additions.
+  for len in [1, 7, 8, 9, 31] {
+    let u: Vector<f64> = Vector::from_vec((0..len).map(|x| x as f64).collect());
+    let v: Vector<f64> = Vector::from_vec((0..len).map(|x| (len - x) as f64).collect());
+    assert_eq!(u.dot(&v).unwrap(), (0..len).map(|x| (x * (len - x)) as f64).sum::<f64>());
+    assert_eq!((u.clone() - v.clone()).unwrap().iter().collect::<Vec<f64>>(), (0..len).map(|x| (2 * x) as f64 - len as f64).collect::<Vec<f64>>());
+    let w: Vector<f32> = Vector::from_vec(vec![2.0; len]);
+    assert_eq!(w.norm(), (4.0 * len as f32).sqrt());
+  }
+
+  let mat: FloatMatrix<f32> = FloatMatrix::from_1d_vec((0..(m * k)).map(|x| ((x % 11) as f32) - 5.0).collect(), m, k);
+  let x: Vector<f32> = Vector::from_vec((0..k).map(|x| ((x % 5) as f32) - 2.0).collect());
+  let y: RowVector<f32> = RowVector::from_vec((0..m).map(|x| ((x % 3) as f32) - 1.0).collect());
+  let mx: Vec<f32> = (0..m).map(|i| (0..k).map(|j| mat[(i, j)] * x[j]).sum()).collect();
+  let ym: Vec<f32> = (0..k).map(|j| (0..m).map(|i| y[i] * mat[(i, j)]).sum()).collect();
+  assert_eq!((mat.clone() * x.clone()).unwrap().iter().collect::<Vec<f32>>(), mx);
+  assert_eq!((mat.to_layout(Layout::ColMajor) * x).unwrap().iter().collect::<Vec<f32>>(), mx);
+  assert_eq!((y.clone() * mat.clone()).unwrap().iter().collect::<Vec<f32>>(), ym);
+  assert_eq!((y * mat.to_layout(Layout::ColMajor)).unwrap().iter().collect::<Vec<f32>>(), ym);
+}
+
+#[test]
+fn strassen_test() {
+  // One level of recursion, with the odd `k` and `n` padded with zeros.
+  let (m, k, n) = (300, 259, 291);
+  let a_vals = fixture(m * k, 7, 23);
+  let b_vals = fixture(k * n, 13, 19);
+  let a: Matrix<i64> = Matrix::from_1d_vec(a_vals.clone(), m, k);
+  let b: Matrix<i64> = Matrix::from_1d_vec(b_vals.clone(), k, n).to_layout(Layout::ColMajor);
+  let product = a.mul_strassen(&b).unwrap();
+  assert_eq!(product.size(), (m, n));
+  let expected = (Matrix::from_1d_vec(a_vals.clone(), m, k) * Matrix::from_1d_vec(b_vals.clone(), k, n)).unwrap();
+  assert_eq!(product.iter().collect::<Vec<i64>>(), expected.iter().collect::<Vec<i64>>());
+
+  // Unsigned differences wrap around, but the product still comes out exact.
+  let unsigned: Vec<u32> = (0..(300 * 300)).map(|x| (x % 7) as u32).collect();
+  let a: Matrix<u32> = Matrix::from_1d_vec(unsigned.clone(), 300, 300);
+  let b: Matrix<u32> = Matrix::from_1d_vec(unsigned.iter().rev().copied().collect(), 300, 300);
+  let expected = naive_product(&a.iter().collect::<Vec<u32>>(), &b.iter().collect::<Vec<u32>>(), 300, 300, 300);
+  assert_eq!(a.mul_strassen(&b).unwrap().iter().collect::<Vec<u32>>(), expected);
+
+  let a: FloatMatrix<f64> = FloatMatrix::from_1d_vec(a_vals.iter().map(|x| *x as f64 / 7.0).collect(), m, k);
+  let b: FloatMatrix<f64> = FloatMatrix::from_1d_vec(b_vals.iter().map(|x| *x as f64 / 3.0).collect(), k, n);
+  let product = a.mul_strassen(&b).unwrap();
+  let expected = (a.clone() * b.clone()).unwrap();
+  assert!(product.iter().zip(expected.iter()).all(|(x, y)| (x - y).abs() < 1e-10));
+
+  // Below the crossover it is the regular product.
+  let small: Matrix<i32> = Matrix::from_1d_vec(vec![1, 2, 3, 4, 5, 6], 2, 3);
+  let other: Matrix<i32> = Matrix::from_1d_vec(vec![1, 0, 0, 1, 1, 1], 3, 2);
+  assert_eq!(small.mul_strassen(&other).unwrap().iter().collect::<Vec<i32>>(), vec![4, 5, 10, 11]);
+  assert!(small.mul_strassen(&small).err() == Some(MatrixError::RowMismatch { expected: 3, found: 2 }));
+}
+
+#[test]
+fn into_test() {
+  // Every shape and layout, including the cycle-following rectangular case.
+  for (rows, cols) in [(1, 1), (1, 5), (5, 1), (2, 3), (4, 7), (6, 6), (9, 4)] {
+    for layout in [Layout::RowMajor, Layout::ColMajor] {
+      let vals: Vec<i32> = (0..(rows * cols) as i32).collect();
+      let original: Matrix<i32> = Matrix::from_1d_vec_with_layout(vals.clone(), rows, cols, layout);
+      let mut transposed: Matrix<i32> = Matrix::from_1d_vec_with_layout(vals.clone(), rows, cols, layout);
+      transposed.transpose_in_place();
+      assert_eq!(transposed.size(), (cols, rows));
+      assert!(transposed.layout() == layout);
+      assert!((0..rows).all(|i| (0..cols).all(|j| transposed.get(j, i) == original.get(i, j))));
+
+      let original: FloatMatrix<f64> = FloatMatrix::from_1d_vec_with_layout(vals.iter().map(|x| *x as f64).collect(), rows, cols, layout);
+      let mut transposed = original.clone();
+      transposed.transpose_in_place();
+      assert_eq!(transposed.size(), (cols, rows));
+      assert!((0..rows).all(|i| (0..cols).all(|j| transposed.get(j, i) == original.get(i, j))));
+    }
+  }
+
+  // The same buffer is reused across iterations, whatever its previous layout.
+  let a: FloatMatrix<f64> = FloatMatrix::from_1d_vec((0..12).map(|x| x as f64 / 4.0).collect(), 3, 4);
+  let b: FloatMatrix<f64> = FloatMatrix::from_1d_vec((0..8).map(|x| x as f64 - 3.0).collect(), 4, 2).to_layout(Layout::ColMajor);
+  let c: FloatMatrix<f64> = FloatMatrix::from_1d_vec((0..12).map(|x| x as f64 * 3.0).collect(), 3, 4).to_layout(Layout::ColMajor);
+  let mut product: FloatMatrix<f64> = FloatMatrix::from_1d_vec_with_layout(vec![9.0; 6], 3, 2, Layout::ColMajor);
+  let mut sum: FloatMatrix<f64> = FloatMatrix::new(3, 4);
+  for _ in 0..3 {
+    a.mul_into(&b, &mut product).unwrap();
+    assert_eq!(product.iter().collect::<Vec<f64>>(), (a.clone() * b.clone()).unwrap().iter().collect::<Vec<f64>>());
+    a.add_into(&c, &mut sum).unwrap();
+    assert_eq!(sum.iter().collect::<Vec<f64>>(), (a.clone() + c.clone()).unwrap().iter().collect::<Vec<f64>>());
+    a.sub_into(&c, &mut sum).unwrap();
+    assert_eq!(sum.iter().collect::<Vec<f64>>(), (a.clone() - c.clone()).unwrap().iter().collect::<Vec<f64>>());
+  }
+  assert!(a.mul_into(&a, &mut product).err() == Some(MatrixError::RowMismatch { expected: 4, found: 3 }));
+  assert!(a.mul_into(&b, &mut sum).err() == Some(MatrixError::ColumnMismatch { expected: 2, found: 4 }));
+  assert!(a.add_into(&b, &mut sum).err() == Some(MatrixError::RowMismatch { expected: 3, found: 4 }));
+
+  let x: Matrix<i64> = Matrix::from_1d_vec(vec![1, 2, 3, 4, 5, 6], 2, 3);
+  let y: Matrix<i64> = Matrix::from_1d_vec(vec![1, 0, 2, 1, 0, 3], 3, 2).to_layout(Layout::ColMajor);
+  let mut out: Matrix<i64> = Matrix::new(2, 2);
+  x.mul_into(&y, &mut out).unwrap();
+  assert_eq!(out.iter().collect::<Vec<i64>>(), vec![5, 11, 14, 23]);
+  let mut out: Matrix<i64> = Matrix::new(2, 3);
+  x.add_into(&x, &mut out).unwrap();
+  assert_eq!(out.iter().collect::<Vec<i64>>(), vec![2, 4, 6, 8, 10, 12]);
+  x.sub_into(&x, &mut out).unwrap();
+  assert_eq!(out.iter().collect::<Vec<i64>>(), vec![0; 6]);
+  assert!(x.sub_into(&y, &mut out).err() == Some(MatrixError::RowMismatch { expected: 2, found: 3 }));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_test() {
+  let m: Matrix<i32> = Matrix::from_1d_vec(vec![1, 2, 3, 4, 5, 6], 2, 3);
+  let json = serde_json::to_string(&m).unwrap();
+  assert_eq!(json, r#"{"rows":2,"cols":3,"layout":"RowMajor","data":[1,2,3,4,5,6]}"#);
+  let back: Matrix<i32> = serde_json::from_str(&json).unwrap();
+  assert_eq!(back.size(), (2, 3));
+  assert_eq!(back.iter().collect::<Vec<i32>>(), m.iter().collect::<Vec<i32>>());
+
+  // Column-major storage round-trips as is, and a missing layout means row-major.
+  let m = m.to_layout(Layout::ColMajor);
+  let back: Matrix<i32> = serde_json::from_str(&serde_json::to_string(&m).unwrap()).unwrap();
+  assert!(back.layout() == Layout::ColMajor);
+  assert_eq!(back.iter().collect::<Vec<i32>>(), m.iter().collect::<Vec<i32>>());
+  let config: Matrix<i32> = serde_json::from_str(r#"{"rows":2,"cols":2,"data":[1,2,3,4]}"#).unwrap();
+  assert!(config.layout() == Layout::RowMajor);
+  assert_eq!(config.get(0, 1), Some(&2));
+
+  let err = serde_json::from_str::<Matrix<i32>>(r#"{"rows":2,"cols":2,"data":[1,2,3]}"#).err().unwrap();
+  assert!(err.to_string().starts_with(&MatrixError::SizeMismatch { expected: 4, found: 3 }.to_string()));
+  let err = serde_json::from_str::<FloatMatrix<f64>>(r#"{"rows":0,"cols":2,"data":[]}"#).err().unwrap();
+  assert!(err.to_string().starts_with(&MatrixError::InvalidDimensions { rows: 0, cols: 2 }.to_string()));
+  let huge = format!(r#"{{"rows":{},"cols":2,"data":[1.0]}}"#, usize::MAX);
+  assert!(serde_json::from_str::<FloatMatrix<f64>>(&huge).is_err());
+
+  let f: FloatMatrix<f64> = FloatMatrix::from_1d_vec((0..12).map(|x| x as f64 / 3.0).collect(), 4, 3).to_layout(Layout::ColMajor);
+  let bytes = bincode::serialize(&f).unwrap();
+  let back: FloatMatrix<f64> = bincode::deserialize(&bytes).unwrap();
+  assert_eq!(back.size(), (4, 3));
+  assert!(back.layout() == Layout::ColMajor);
+  assert_eq!(back.iter().collect::<Vec<f64>>(), f.iter().collect::<Vec<f64>>());
+}